@@ -31,6 +31,10 @@ struct Opts {
     #[clap(long, number_of_values = 2)]
     delimiters: Option<Vec<String>>,
 
+    /// Compile in production mode, omitting dev-only output
+    #[clap(long)]
+    prod: bool,
+
     /// Display the token stream produced by scanner
     #[clap(short = 's', long)]
     dump_scan: bool,
@@ -69,6 +73,7 @@ fn process(opts: Opts) -> Result<CliInput> {
             runtime_module_name: "vue".into(),
         },
         cache_handlers: true,
+        is_dev: !opts.prod,
         ..compile_option(Rc::new(err_handle))
     };
     let show = ShowOption {
@@ -1,9 +1,10 @@
 mod code_writer;
 
 use crate::converter::BaseRoot;
+use crate::flags::RuntimeHelper as RH;
 use crate::SFCInfo;
 use crate::ir::{self as C, ConvertInfo, IRNode, IRRoot};
-use code_writer::CodeWriter;
+pub(crate) use code_writer::CodeWriter;
 
 use smallvec::{smallvec, SmallVec};
 use std::marker::PhantomData;
@@ -22,7 +23,7 @@ pub trait CodeGenerator {
     fn generate<'a>(&self, node: Self::IR<'a>, info: Self::Info<'a>) -> Self::Output;
 }
 
-#[derive(PartialEq, Eq, Clone)]
+#[derive(PartialEq, Eq, Clone, Hash)]
 pub enum ScriptMode {
     Function {
         /// Transform expressions like {{ foo }} to `_ctx.foo`.
@@ -51,6 +52,19 @@ pub struct CodeGenerateOption {
     pub mode: ScriptMode,
     pub source_map: bool,
     pub helper_strs: &'static [&'static str],
+    pub pure_annotation: bool,
+    pub minify: bool,
+    pub hoist_prefix: String,
+    /// Prefix of helper local names, e.g. `_` in `_createVNode`.
+    pub helper_prefix: String,
+    /// Import or destructure helpers before render function.
+    /// Disable it when the caller declares helpers for many templates.
+    /// Function mode then declares render without `return` as well.
+    pub helper_imports: bool,
+    /// Name of the render function, unique per template in a batch.
+    pub render_name: String,
+    /// Wrap root in a Fragment even if there is only one root node.
+    pub force_fragment_root: bool,
 }
 impl CodeGenerateOption {
     fn use_with_scope(&self) -> bool {
@@ -72,6 +86,13 @@ impl Default for CodeGenerateOption {
             },
             source_map: false,
             helper_strs: &[],
+            pure_annotation: false,
+            minify: false,
+            hoist_prefix: "_hoisted_".into(),
+            helper_prefix: RH::ALIAS_PREFIX.into(),
+            helper_imports: true,
+            render_name: "render".into(),
+            force_fragment_root: false,
         }
     }
 }
@@ -9,7 +9,7 @@ use crate::ir::{self as C, IRNode, JsExpr as Js, RenderSlotIR, RuntimeDir, VNode
 use crate::util::{get_vnode_call_helper, is_simple_identifier, VStr};
 use crate::SFCInfo;
 
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 
 use smallvec::SmallVec;
 use std::{
@@ -61,6 +61,8 @@ pub struct CodeWriter<'a, T: ioWrite> {
     cache_count: usize,
     in_alterable: bool,
     helpers: HelperCollector,
+    /// hoists declared by other templates in a batch, by index
+    shared_hoists: FxHashMap<usize, String>,
 }
 impl<'a, T: ioWrite> CodeWriter<'a, T> {
     pub fn new(writer: T, option: Rc<CodeGenerateOption>, sfc_info: &'a SFCInfo<'a>) -> Self {
@@ -73,8 +75,13 @@ impl<'a, T: ioWrite> CodeWriter<'a, T> {
             cache_count: 0,
             in_alterable: false,
             helpers: Default::default(),
+            shared_hoists: Default::default(),
         }
     }
+    /// declare helpers for expressions generated outside of a root
+    pub(crate) fn set_helpers(&mut self, helpers: HelperCollector) {
+        self.helpers = helpers;
+    }
 }
 
 impl<'a, T: ioWrite> CoreCodeGenerator<BaseConvertInfo<'a>> for CodeWriter<'a, T> {
@@ -84,6 +91,7 @@ impl<'a, T: ioWrite> CoreCodeGenerator<BaseConvertInfo<'a>> for CodeWriter<'a, T
         self.generate_function_signature()?;
         self.generate_with_scope()?;
         self.generate_assets(&root.top_scope)?;
+        self.generate_temps(&mut root.top_scope)?;
         self.write_str("return ")
     }
     fn generate_epilogue(&mut self) -> Output {
@@ -108,6 +116,8 @@ impl<'a, T: ioWrite> CoreCodeGenerator<BaseConvertInfo<'a>> for CodeWriter<'a, T
         self.write_str(")")
     }
     fn generate_if(&mut self, i: BaseIf<'a>) -> Output {
+        // branches hold IR children rather than JsExpr, so the conditional
+        // chain `(a) ? A : (b) ? B : comment` is written here directly.
         let mut indent = 0;
         for branch in i.branches {
             if branch.condition.is_none() {
@@ -128,9 +138,7 @@ impl<'a, T: ioWrite> CoreCodeGenerator<BaseConvertInfo<'a>> for CodeWriter<'a, T
         }
         if !self.in_alterable {
             // generate default v-else comment
-            let s = if self.option.is_dev { "'v-if'" } else { "''" };
-            let comment = Js::Call(RH::CREATE_COMMENT, vec![Js::Src(s), Js::Src("true")]);
-            self.generate_js_expr(comment)?;
+            self.generate_js_expr(Js::v_if_comment(self.option.is_dev))?;
         } else {
             // generate undefined for alterable_slots
             self.write_str("undefined")?;
@@ -185,6 +193,10 @@ impl<'a, T: ioWrite> CoreCodeGenerator<BaseConvertInfo<'a>> for CodeWriter<'a, T
     fn generate_cache(&mut self, c: BaseCache<'a>) -> Self::Written {
         use C::CacheKind as CK;
         match c.kind {
+            CK::Once if matches!(*c.child, IRNode::Hoisted(_)) => {
+                // static v-once is hoisted, no cache needed
+                return self.generate_ir(*c.child);
+            }
             CK::Once => {
                 write!(self.writer, "_cache[{}] || (", self.cache_count)?;
                 self.indent()?;
@@ -214,7 +226,7 @@ impl<'a, T: ioWrite> CoreCodeGenerator<BaseConvertInfo<'a>> for CodeWriter<'a, T
                 self.write_str("const _memo=(")?;
                 self.generate_js_expr(expr)?;
                 self.write_str(")")?;
-                self.newline()?;
+                self.end_statement()?;
                 self.write_str("if (_cached")?;
                 if let Some(key) = v_for_key {
                     self.write_str(" && _cache.key === ")?;
@@ -223,12 +235,12 @@ impl<'a, T: ioWrite> CoreCodeGenerator<BaseConvertInfo<'a>> for CodeWriter<'a, T
                 self.write_str(" && ")?;
                 self.write_helper(RH::IS_MEMO_SAME)?;
                 self.write_str("(_cached, _memo)) return _cached")?;
-                self.newline()?;
+                self.end_statement()?;
                 self.write_str("const _item = ")?;
                 self.generate_ir(*c.child)?;
-                self.newline()?;
+                self.end_statement()?;
                 self.write_str("_item.memo = _memo")?;
-                self.newline()?;
+                self.end_statement()?;
                 self.write_str("return _item")?;
             }
         }
@@ -297,7 +309,10 @@ impl<'a, T: ioWrite> CoreCodeGenerator<BaseConvertInfo<'a>> for CodeWriter<'a, T
         self.generate_js_expr(call)
     }
     fn generate_hoisted(&mut self, h: usize) -> Self::Written {
-        write!(self.writer, "_hoisted_{h}")
+        if let Some(name) = self.shared_hoists.get(&h) {
+            return self.writer.write_str(name);
+        }
+        write!(self.writer, "{}{}", self.option.hoist_prefix, h)
     }
 }
 
@@ -310,12 +325,18 @@ impl<'a, T: ioWrite> CodeWriter<'a, T> {
         if root.body.is_empty() {
             self.write_str("null")?;
         } else {
-            let ir = if root.body.len() == 1 {
+            let ir = if root.body.len() == 1 && !self.option.force_fragment_root {
                 root.body.pop().unwrap()
             } else {
+                let mut patch_flag = PatchFlag::STABLE_FRAGMENT;
+                // devtools uses the flag to identify synthetic root fragment
+                if self.option.is_dev {
+                    patch_flag |= PatchFlag::DEV_ROOT_FRAGMENT;
+                }
                 IRNode::VNodeCall(VNodeIR {
                     tag: Js::Symbol(RH::FRAGMENT),
                     children: root.body,
+                    patch_flag,
                     ..VNodeIR::default()
                 })
             };
@@ -337,11 +358,11 @@ impl<'a, T: ioWrite> CodeWriter<'a, T> {
     }
     fn gen_function_preamble(&mut self, top: &mut TopScope<'a>, global_name: &str) -> Output {
         debug_assert!(top.helpers == self.helpers);
-        if !self.helpers.is_empty() {
-            if self.option.use_with_scope() {
+        if self.option.helper_imports && !self.helpers.is_empty() {
+            if self.use_with_scope() {
                 self.write_str("const _Vue = ")?;
                 self.write_str(global_name)?;
-                self.newline()?;
+                self.end_statement()?;
                 // helpers are declared inside with block, but hoists
                 // are lifted out so we need extract hoist helper here.
                 if !top.hoists.is_empty() {
@@ -353,9 +374,18 @@ impl<'a, T: ioWrite> CodeWriter<'a, T> {
                 self.gen_helper_destruct(helper, global_name)?;
             }
         }
+        self.gen_component_exprs(top)?;
         self.gen_hoists(top)?;
         self.newline()?;
-        self.write_str("return ")
+        if self.option.helper_imports {
+            self.write_str("return ")
+        } else {
+            Ok(())
+        }
+    }
+    /// inline mode accesses setup bindings directly, never uses with(_ctx)
+    fn use_with_scope(&self) -> bool {
+        self.option.use_with_scope() && !self.sfc_info.inline
     }
     fn should_gen_scope_id(&self) -> bool {
         self.sfc_info.scope_id.is_some() && matches!(self.option.mode, ScriptMode::Module { .. })
@@ -365,15 +395,16 @@ impl<'a, T: ioWrite> CodeWriter<'a, T> {
             self.helpers.collect(RH::PUSH_SCOPE_ID);
             self.helpers.collect(RH::POP_SCOPE_ID);
         }
-        if !self.helpers.is_empty() {
+        if self.option.helper_imports && !self.helpers.is_empty() {
             let helpers = self.helpers.clone();
             self.gen_helper_import(helpers, module_name)?;
             self.newline()?;
         }
         self.gen_imports(top)?;
+        self.gen_component_exprs(top)?;
         self.gen_hoists(top)?;
         self.newline()?;
-        if self.sfc_info.inline {
+        if !self.sfc_info.inline {
             self.write_str("export ")
         } else {
             Ok(())
@@ -387,7 +418,7 @@ impl<'a, T: ioWrite> CodeWriter<'a, T> {
         self.write_str("} from \"")?;
         self.write_str(from)?;
         self.write_str("\"")?;
-        self.newline()
+        self.end_statement()
     }
     fn gen_helper_destruct(&mut self, helpers: HelperCollector, from: &str) -> Output {
         self.write_str("const {")?;
@@ -396,13 +427,14 @@ impl<'a, T: ioWrite> CodeWriter<'a, T> {
         self.deindent()?;
         self.write_str("} = ")?;
         self.write_str(from)?;
-        self.newline()
+        self.end_statement()
     }
     fn gen_helper_import_list(&mut self, helpers: HelperCollector, sep: &str) -> Output {
         for rh in helpers.into_iter() {
             self.write_str(rh.helper_str(self.option.helper_strs))?;
             self.write_str(sep)?;
-            self.write_str(" _")?;
+            self.write_str(" ")?;
+            self.writer.write_str(&self.option.helper_prefix)?;
             self.write_str(rh.helper_str(self.option.helper_strs))?;
             self.write_str(", ")?;
         }
@@ -419,8 +451,16 @@ impl<'a, T: ioWrite> CodeWriter<'a, T> {
             self.write_str("import ")?;
             self.generate_js_expr(impt.exp)?;
             self.write_str(" from ")?;
-            self.write_str(impt.path)?;
-            self.newline()?;
+            self.generate_js_expr(Js::str_lit(impt.path))?;
+            self.end_statement()?;
+        }
+        Ok(())
+    }
+    fn gen_component_exprs(&mut self, top: &mut TopScope<'a>) -> Output {
+        let exprs = std::mem::take(&mut top.component_exprs);
+        for (i, expr) in exprs.into_iter().enumerate() {
+            write!(self.writer, "const _component_expr_{} = {}", i, expr)?;
+            self.end_statement()?;
         }
         Ok(())
     }
@@ -434,25 +474,33 @@ impl<'a, T: ioWrite> CodeWriter<'a, T> {
             self.write_str("const _withScopeId = n => (")?;
             self.write_helper(RH::PUSH_SCOPE_ID)?;
             let scope_id = self.sfc_info.scope_id.as_ref().unwrap();
-            write!(self.writer, "({}),n=n(),", scope_id)?;
+            write!(self.writer, "(\"{}\"),n=n(),", scope_id)?;
             self.write_helper(RH::POP_SCOPE_ID)?;
             self.write_str("(),n)")?;
-            self.newline()?;
+            self.end_statement()?;
         }
         let hoists = std::mem::take(&mut top.hoists);
         for (i, hoist) in hoists.into_iter().enumerate() {
+            if let Hoist::Shared(name) = hoist {
+                self.shared_hoists.insert(i, name);
+                continue;
+            }
             let scope_id_wrapper = gen_scope_id && matches!(hoist, Hoist::FullElement(_));
             let wrapper = if scope_id_wrapper {
                 "_withScopeId(() => "
             } else {
                 ""
             };
-            write!(self.writer, "const _hoisted_{} = {}", i, wrapper)?;
+            let prefix = &self.option.hoist_prefix;
+            write!(self.writer, "const {}{} = {}", prefix, i, wrapper)?;
+            if self.option.pure_annotation && matches!(hoist, Hoist::FullElement(_)) {
+                self.write_str("/*#__PURE__*/")?;
+            }
             self.generate_one_hoist(hoist)?;
             if scope_id_wrapper {
                 self.write_str(")")?;
             }
-            self.newline()?;
+            self.end_statement()?;
         }
         Ok(())
     }
@@ -462,8 +510,9 @@ impl<'a, T: ioWrite> CodeWriter<'a, T> {
         match hoist {
             H::FullElement(e) => self.generate_vnode(e),
             H::StaticProps(p) => self.generate_js_expr(p),
-            H::ChildrenArray(_) => todo!(),
+            H::ChildrenArray(c) => self.generate_children(c),
             H::DynamicPropsHint(d) => self.gen_dynamic_props(d),
+            H::Shared(name) => self.write_str(&name),
         }
     }
 
@@ -475,24 +524,29 @@ impl<'a, T: ioWrite> CodeWriter<'a, T> {
         } else {
             "_ctx, _cache"
         };
-        // NB: vue uses arrow func for inline mode.
-        // but it makes no diff in Vue runtime implementation?
-        self.write_str("function render(")?;
-        self.write_str(args)?;
-        self.write_str(") {")?;
+        // inline mode emits an anonymous function to be inlined in setup()
+        if option.inline {
+            self.write_str("(")?;
+            self.write_str(args)?;
+            self.write_str(") => {")?;
+        } else {
+            write!(self.writer, "function {}(", self.option.render_name)?;
+            self.write_str(args)?;
+            self.write_str(") {")?;
+        }
         self.closing_brackets += 1;
         self.indent()
     }
     /// with (ctx) for not prefixIdentifier
     fn generate_with_scope(&mut self) -> Output {
         let helpers = self.helpers.clone();
-        if !self.option.use_with_scope() {
+        if !self.use_with_scope() {
             return Ok(());
         }
         self.write_str("with (_ctx) {")?;
         self.closing_brackets += 1;
         self.indent()?;
-        if helpers.is_empty() {
+        if !self.option.helper_imports || helpers.is_empty() {
             return Ok(());
         }
         // function mode const declarations should be inside with block
@@ -513,6 +567,20 @@ impl<'a, T: ioWrite> CodeWriter<'a, T> {
         }
         Ok(())
     }
+    /// repeated expressions lifted by TempLifter
+    fn generate_temps(&mut self, top: &mut TopScope<'a>) -> Output {
+        if top.temps.is_empty() {
+            return Ok(());
+        }
+        self.newline()?;
+        let temps = std::mem::take(&mut top.temps);
+        for (i, temp) in temps.into_iter().enumerate() {
+            write!(self.writer, "let _temp{} = ", i)?;
+            self.generate_js_expr(temp)?;
+            self.end_statement()?;
+        }
+        Ok(())
+    }
 
     fn gen_concate_str(&mut self, t: SmallVec<[Js<'a>; 1]>) -> Output {
         let mut texts = t.into_iter();
@@ -529,13 +597,14 @@ impl<'a, T: ioWrite> CodeWriter<'a, T> {
 
     fn generate_children(&mut self, children: Vec<BaseIR<'a>>) -> Output {
         debug_assert!(!children.is_empty());
-        let fast = if let IRNode::TextCall(t) = &children[0] {
-            t.fast_path
-        } else {
-            false
+        let fast = match &children[0] {
+            IRNode::TextCall(t) => t.fast_path,
+            // component slots object
+            IRNode::VSlotUse(_) => true,
+            _ => false,
         };
         if fast {
-            // generate sole text node without []
+            // generate sole text node or slots without []
             let ir = children.into_iter().next().unwrap();
             return self.generate_ir(ir);
         }
@@ -560,7 +629,9 @@ impl<'a, T: ioWrite> CodeWriter<'a, T> {
         self.generate_js_expr(f.source)?;
         self.write_str(", ")?;
         let p = f.parse_result;
-        let mut params = vec![Some(p.value), p.key, p.index];
+        // omitted alias like `(v, , i)` is replaced by placeholder
+        let alias = |a: Option<Js<'a>>| a.filter(|a| !matches!(a, Js::Param("")));
+        let mut params = vec![Some(p.value), alias(p.key), alias(p.index)];
         if has_memo {
             params.push(Some(Js::Src("_cached")));
             self.gen_func_expr(params, *f.child, /*need_return*/ false)?;
@@ -681,6 +752,8 @@ impl<'a, T: ioWrite> CodeWriter<'a, T> {
         if !v.is_block {
             return gen_vnode_real(self, v);
         }
+        // dynamicChildren are tracked at runtime by openBlock. createBlock's
+        // fourth argument is the patch flag, so they are not collected here.
         self.gen_open_block(v.disable_tracking, move |gen| gen_vnode_real(gen, v))
     }
     fn gen_open_block<K>(&mut self, no_track: bool, cont: K) -> Output
@@ -699,6 +772,9 @@ impl<'a, T: ioWrite> CodeWriter<'a, T> {
     }
 
     fn newline(&mut self) -> Output {
+        if self.option.minify {
+            return Ok(());
+        }
         self.write_str("\n")?;
         // TODO: use exponential adding + lazy static
         for _ in 0..self.indent_level {
@@ -706,6 +782,14 @@ impl<'a, T: ioWrite> CodeWriter<'a, T> {
         }
         Ok(())
     }
+    /// newline cannot separate statements in minified output
+    fn end_statement(&mut self) -> Output {
+        if self.option.minify {
+            self.write_str(";")
+        } else {
+            self.newline()
+        }
+    }
     fn indent(&mut self) -> Output {
         self.indent_level += 1;
         self.newline()
@@ -732,7 +816,7 @@ impl<'a, T: ioWrite> CodeWriter<'a, T> {
     #[inline(always)]
     fn write_helper(&mut self, h: RH) -> Output {
         debug_assert!(self.helpers.contains(h));
-        self.write_str("_")?;
+        self.writer.write_str(&self.option.helper_prefix)?;
         self.write_str(h.helper_str(self.option.helper_strs))
     }
     #[inline(always)]
@@ -993,15 +1077,16 @@ fn gen_assets<'a, T: ioWrite>(
         gen.write_str(" = ")?;
         gen.write_helper(resolver)?;
         gen.write_str("(")?;
-        let raw = if resolver == RH::RESOLVE_COMPONENT {
+        let mut raw = if resolver == RH::RESOLVE_COMPONENT {
             *asset.clone().unbe_component()
         } else {
             *asset.clone().unbe_directive()
         };
-        raw.write_to(&mut gen.writer)?;
+        // asset name is passed as string literal
+        raw.be_js_str().write_to(&mut gen.writer)?;
         gen.write_str(hint)?;
         gen.write_str(")")?;
-        gen.newline()?;
+        gen.end_statement()?;
     }
     Ok(())
 }
@@ -1032,9 +1117,16 @@ mod test {
     use super::*;
     use crate::cast;
     use crate::{BindingMetadata, BindingTypes};
-    fn gen<'a>(mut ir: BaseRoot<'a>, info: &'a SFCInfo<'a>) -> String {
+    fn gen<'a>(ir: BaseRoot<'a>, info: &'a SFCInfo<'a>) -> String {
+        gen_with_option(ir, info, Default::default())
+    }
+    fn gen_with_option<'a>(
+        mut ir: BaseRoot<'a>,
+        info: &'a SFCInfo<'a>,
+        option: CodeGenerateOption,
+    ) -> String {
         ir.top_scope.helpers.ignore_missing();
-        let mut writer = CodeWriter::new(vec![], Default::default(), info);
+        let mut writer = CodeWriter::new(vec![], Rc::new(option), info);
         writer.generate_root(ir).unwrap();
         String::from_utf8(writer.writer.inner).unwrap()
     }
@@ -1052,6 +1144,86 @@ mod test {
         assert!(s.contains("_createTextVNode(_toDisplayString(world))"));
     }
     #[test]
+    fn test_dev_root_fragment() {
+        let dev = CodeGenerateOption {
+            is_dev: true,
+            ..Default::default()
+        };
+        let info = SFCInfo::default();
+        let s = gen_with_option(base_convert("<p/><p/>"), &info, dev);
+        assert!(s.contains("_Fragment"), "{}", s);
+        let flag = PatchFlag::STABLE_FRAGMENT | PatchFlag::DEV_ROOT_FRAGMENT;
+        assert!(s.contains(&format!("], {} /*", flag.bits())), "{}", s);
+        let prod = CodeGenerateOption {
+            is_dev: false,
+            ..Default::default()
+        };
+        let s = gen_with_option(base_convert("<p/><p/>"), &info, prod);
+        let flag = PatchFlag::STABLE_FRAGMENT.bits();
+        assert!(s.contains(&format!("], {})", flag)), "{}", s);
+    }
+    #[test]
+    fn test_dev_v_if_comment() {
+        let info = SFCInfo::default();
+        let gen = |is_dev| {
+            let option = CodeGenerateOption {
+                is_dev,
+                ..Default::default()
+            };
+            gen_with_option(base_convert("<p v-if='a'/>"), &info, option)
+        };
+        let s = gen(true);
+        assert!(s.contains(r#"_createCommentVNode("v-if", true)"#), "{}", s);
+        let s = gen(false);
+        assert!(s.contains(r#"_createCommentVNode("", true)"#), "{}", s);
+    }
+    #[test]
+    fn test_temps() {
+        use crate::transformer::{lift_temps::TempLifter, BaseTransformer, Transformer};
+        let info = SFCInfo::default();
+        let mut ir = base_convert("<p :style='{color: 1}'/><p :style='{color: 1}'/>");
+        BaseTransformer::transform(&mut ir, TempLifter);
+        let s = gen(ir, &info);
+        assert!(s.contains("let _temp0 = {color: 1}"), "{}", s);
+        assert_eq!(s.matches("style: _temp0").count(), 2, "{}", s);
+    }
+    fn gen_js(e: Js) -> String {
+        let info = SFCInfo::default();
+        let mut writer = CodeWriter::new(vec![], Rc::new(Default::default()), &info);
+        writer.generate_js_expr(e).unwrap();
+        String::from_utf8(writer.writer.inner).unwrap()
+    }
+    #[test]
+    fn test_array_codegen() {
+        assert_eq!(gen_js(Js::Array(vec![])), "[]");
+        let arr = Js::Array(vec![Js::simple("a"), Js::str_lit("b"), Js::Num(1)]);
+        assert_eq!(gen_js(arr), r#"[a, "b", 1]"#);
+    }
+    #[test]
+    fn test_object_codegen() {
+        assert_eq!(gen_js(Js::Props(vec![])), "{}");
+        let obj = Js::Props(vec![
+            (Js::str_lit("id"), Js::simple("a")),
+            (Js::str_lit("data-x"), Js::str_lit("b")),
+            (Js::simple("key"), Js::Num(1)),
+        ]);
+        let s = gen_js(obj);
+        assert!(s.contains("id: a,"), "{}", s);
+        assert!(s.contains(r#""data-x": "b","#), "{}", s);
+        assert!(s.contains("[key]: 1,"), "{}", s);
+    }
+    #[test]
+    fn test_v_for_alias_params() {
+        let s = base_gen("<p v-for='n in list'/>");
+        assert!(s.contains("_renderList(list, (n) => {"), "{}", s);
+        let s = base_gen("<p v-for='(v, k) in obj'/>");
+        assert!(s.contains("_renderList(obj, (v, k) => {"), "{}", s);
+        let s = base_gen("<p v-for='(v, k, i) in obj'/>");
+        assert!(s.contains("_renderList(obj, (v, k, i) => {"), "{}", s);
+        let s = base_gen("<p v-for='(v, , i) in 10'/>");
+        assert!(s.contains("_renderList(10, (v, _1, i) => {"), "{}", s);
+    }
+    #[test]
     fn test_text_merge() {
         let info = SFCInfo::default();
         let mut ir = base_convert("hello{{world}}");
@@ -1095,6 +1267,9 @@ mod test {
         assert!(s.contains(r#"id: "id""#), "{}", s);
         let s = base_gen("<button aria-label='close'/>");
         assert!(s.contains(r#""aria-label": "close""#), "{}", s);
+        // interpolation in static attribute is reported but kept literal
+        let s = base_gen("<p id='{{ a }}'/>");
+        assert!(s.contains(r#"id: "{{ a }}""#), "{}", s);
     }
     #[test]
     fn test_v_bind_shorthand() {
@@ -1105,6 +1280,8 @@ mod test {
         assert!(s.contains("b: b,"), "{}", s);
         assert!(s.contains("PROPS"), "{}", s);
         let s = base_gen("<p :prop />");
+        assert!(s.contains("prop: prop"), "{}", s);
+        let s = base_gen("<p :prop='' />");
         assert!(s.contains(r#"prop: """#), "{}", s);
     }
     #[test]
@@ -1144,6 +1321,26 @@ mod test {
         assert!(s.contains("createCommentVNode"), "{}", s);
     }
 
+    #[test]
+    fn test_v_if_else_ternary() {
+        let s = base_gen("<p v-if='a'/><i v-else/>");
+        let expected = r#"
+    return (a)
+      ? _createElementVNode("p")
+      : _createElementVNode("i")
+"#;
+        assert!(s.contains(expected), "{}", s);
+        let s = base_gen("<p v-if='a'/><i v-else-if='b'/>");
+        let expected = r#"
+    return (a)
+      ? _createElementVNode("p")
+      : (b)
+        ? _createElementVNode("i")
+        : _createCommentVNode("v-if", true)
+"#;
+        assert!(s.contains(expected), "{}", s);
+    }
+
     #[test]
     fn test_v_for() {
         let s = base_gen("<p v-for='a in b'/>");
@@ -1283,5 +1480,6 @@ mod test {
         assert!(s.contains("import"), "{}", s);
         assert!(s.contains("createTextVNode as _createTextVNode"), "{}", s);
         assert!(s.contains("from \"vue\""), "{}", s);
+        assert!(s.contains("export function render("), "{}", s);
     }
 }
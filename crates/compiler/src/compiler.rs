@@ -3,13 +3,14 @@ use super::{
     codegen::{CodeGenerateOption, CodeGenerator, CodeGen, ScriptMode, CodeGenInfo},
     converter::{
         no_op_directive_convert, BaseConvertInfo as BaseInfo, BaseConverter, BaseRoot,
-        ConvertOption, Converter, DirConvertFn, V_BIND, V_MODEL,
+        AssetUrlTransform, ComponentResolver, ConvertOption, Converter, DirConvertFn, V_BIND, V_IS,
+        V_MODEL,
     },
-    error::{NoopErrorHandler, RcErrHandle},
-    flags::RuntimeHelper,
-    parser::{Element, ParseOption, Parser, WhitespaceStrategy, AstRoot},
+    error::{FilenameErrorHandler, NoopErrorHandler, RcErrHandle},
+    flags::{HelperCollector, RuntimeHelper},
+    parser::{Element, ParseMode, ParseOption, Parser, WhitespaceStrategy, AstRoot},
     scanner::{ScanOption, Scanner, TextMode, Tokens},
-    transformer::{BaseTransformer, CorePass, TransformOption, Transformer},
+    transformer::{BaseTransformer, CorePass, DepthLimit, TransformOption},
     util::{no, yes},
     Namespace,
     transformer::{
@@ -19,12 +20,20 @@ use super::{
         optimize_text::TextOptimizer,
         pass::{Scope, SharedInfoPasses},
         process_expression::ExpressionProcessor,
-        hoist_static::HoistStatic,
+        hoist_static::{HoistSink, HoistStatic},
+        lift_temps::TempLifter,
+        track_depth::DepthTracker,
+        warn_constant::ConstantWarner,
+        warn_dynamic_props::DynamicPropsWarner,
     },
 };
 
-use rustc_hash::FxHashMap;
-use std::{io, rc::Rc, marker::PhantomData};
+pub mod batch;
+pub mod cache;
+pub mod expression;
+
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::{collections::BTreeSet, io, rc::Rc, marker::PhantomData};
 
 pub struct CompileOption {
     /// e.g. platform native elements, e.g. `<div>` for browsers
@@ -54,9 +63,17 @@ pub struct CompileOption {
     /// @default ['{{', '}}']
     pub delimiters: (String, String),
 
+    /// Tags whose content is kept verbatim as raw text, e.g. custom `<docs>`.
+    /// @default empty
+    pub raw_elements: BTreeSet<String>,
+
     /// Whitespace handling strategy
     pub whitespace: WhitespaceStrategy,
 
+    /// Self-closing and tag name matching rules, see ParseMode.
+    /// @default ParseMode::Sfc
+    pub parse_mode: ParseMode,
+
     /// platform speicific helper
     pub helper_strs: &'static [&'static str],
 
@@ -69,9 +86,43 @@ pub struct CompileOption {
     /// An object of { name: transform } to be applied to every directive attribute
     /// node found on element nodes.
     pub directive_converters: FxHashMap<&'static str, DirConvertFn>,
+    /// Customize how user components are referenced instead of `resolveComponent`,
+    /// e.g. referencing an imported identifier or an async component expression.
+    /// @default None
+    pub component_resolver: Option<ComponentResolver>,
+    /// Rewrite static asset urls, e.g. `<img src="./a.png">`, into module imports.
+    /// Ignored outside module mode since function mode cannot import.
+    /// @default None
+    pub transform_asset_url: Option<AssetUrlTransform>,
+    /// Compile removed `v-is` as dynamic component like Vue 3.0 did.
+    /// Otherwise `v-is` reports an error to help migration.
+    /// @default false
+    pub compat_v_is: bool,
+    /// Keep original attribute casing on custom elements in Html parse mode,
+    /// since web components may read case sensitive attributes.
+    /// Attribute names on other elements are lowercased in Html mode.
+    /// @default false
+    pub preserve_custom_element_attr_case: bool,
+    /// Compile v-model on custom elements like on components, i.e.
+    /// `modelValue` prop plus `onUpdate:modelValue` listener without
+    /// runtime directive. Otherwise custom elements use vModelText.
+    /// @default false
+    pub custom_element_v_model: bool,
     /// Hoist static VNodes and props objects to `_hoisted_x` constants
     /// @default false
     pub hoist_static: bool,
+    /// Name prefix of hoisted constants. Hoists are numbered from 0
+    /// in the order they are met in the template.
+    /// @default "_hoisted_"
+    pub hoist_prefix: String,
+    /// Deduplicate identical hoists across templates compiled by
+    /// `compile_many`. Single template compiles keep their own hoists.
+    /// @default None
+    pub hoist_sink: Option<Rc<HoistSink>>,
+    /// Prefix of helper local names in generated code, applied to both
+    /// helper imports and call sites, e.g. `_` in `_createVNode`.
+    /// @default "_"
+    pub helper_prefix: String,
     /// Cache v-on handlers to avoid creating new inline functions on each render,
     /// also avoids the need for dynamically patching the handlers by wrapping it.
     /// e.g `@click="foo"` by default is compiled to `{ onClick: foo }`. With this
@@ -95,12 +146,69 @@ pub struct CompileOption {
     /// Generate source map?
     /// @default false
     pub source_map: bool,
+    /// Serialize the transformed IR to JSON instead of generating JS code.
+    /// Useful for tooling written in other languages.
+    /// @default false
+    #[cfg(feature = "serde")]
+    pub emit_ir_json: bool,
+    /// Annotate hoisted vnode calls with `/*#__PURE__*/` so bundlers
+    /// can tree-shake them if unused.
+    /// @default false
+    pub pure_annotation: bool,
+    /// Omit newlines and indentation in output. String literals are intact.
+    /// @default false
+    pub minify: bool,
     /// Whether the output JS needs re-rendering when Vue runtime data change.
     /// e.g. SSR can set it to false since SSR is executed only once per request.
     /// @default true
     pub need_reactivity: bool,
+    /// Trim leading/trailing whitespace in static class/style attribute values
+    /// and collapse whitespace in class lists, e.g. `class="  a   b "` to `a b`.
+    /// @default false
+    pub trim_static_attrs: bool,
+    /// Parse static style attribute into an object at compile time, e.g.
+    /// `style="font-size: 14px"` to `{ fontSize: "14px" }`.
+    /// @default false
+    pub parse_static_style: bool,
+    /// Max element nesting depth allowed in template. Deeper elements are
+    /// dropped with an error instead of overflowing the stack. It also limits
+    /// IR nesting visited by transformers.
+    /// @default 128
+    pub max_depth: usize,
+    /// Report the max IR depth met during transformation in IRRoot.
+    /// @default false
+    pub report_depth: bool,
+    /// Warn interpolations that are pure constants, e.g. {{ 1 + 1 }}.
+    /// @default false
+    pub warn_constant: bool,
+    /// Warn inline v-on statements that have more tokens than the limit.
+    /// @default None
+    pub max_handler_tokens: Option<usize>,
+    /// Warn elements with more dynamic props than the limit. A preceding
+    /// `<!-- @vue-ignore -->` comment suppresses it if comments are preserved.
+    /// @default None
+    pub max_dynamic_props: Option<usize>,
+    /// Inject default keys to children of v-if branches.
+    /// Disable it if keys are supplied externally.
+    /// @default true
+    pub inject_key: bool,
+    /// Always compile root to a Fragment, even with a single root node.
+    /// Useful for partial templates like slot content.
+    /// @default false
+    pub force_fragment_root: bool,
+    /// Extra global identifiers that expressions can access without `_ctx.`
+    /// prefix, merged with built-in ones like `Math` and `JSON`.
+    /// @default empty
+    pub global_allow_list: FxHashSet<String>,
+    /// Runtime helpers the template must not use, e.g. `Suspense`.
+    /// Each used one is reported as ForbiddenHelper.
+    /// @default empty
+    pub deny_helpers: HelperCollector,
     /// Custom error reporter. Default is noop.
     pub error_handler: RcErrHandle,
+    /// Template file name prefixed to error messages.
+    /// @default None
+    pub filename: Option<String>,
     // deleted options
     // nodeTransforms?: NodeTransform[]
     // transformHoist?: HoistTransform | null
@@ -111,7 +219,6 @@ pub struct CompileOption {
     // moved to SFCInfo
     // bindingMetadata?: BindingMetadata
     // inline?: boolean
-    // filename?: string
     // scopeId?: string | null
     // slotted?: boolean
 
@@ -127,6 +234,7 @@ impl Default for CompileOption {
         let mut directive_converters = FxHashMap::default();
         directive_converters.insert(V_BIND.0, V_BIND.1);
         directive_converters.insert(V_MODEL.0, V_MODEL.1);
+        directive_converters.insert(V_IS.0, V_IS.1);
         directive_converters.insert("on", no_op_directive_convert);
         Self {
             is_native_tag: yes,
@@ -137,20 +245,46 @@ impl Default for CompileOption {
             get_namespace: |_, _| Namespace::Html,
             get_text_mode: |_| TextMode::Data,
             delimiters: ("{{".into(), "}}".into()),
+            raw_elements: BTreeSet::new(),
             whitespace: WhitespaceStrategy::Preserve,
+            parse_mode: ParseMode::Sfc,
             helper_strs: &[],
             preserve_comments: None,
             is_dev: true,
             directive_converters,
+            component_resolver: None,
+            transform_asset_url: None,
+            compat_v_is: false,
+            preserve_custom_element_attr_case: false,
+            custom_element_v_model: false,
             hoist_static: false,
+            hoist_prefix: "_hoisted_".into(),
+            hoist_sink: None,
+            helper_prefix: "_".into(),
             cache_handlers: false,
             mode: ScriptMode::Function {
                 prefix_identifier: false,
                 runtime_global_name: "Vue".into(),
             },
             source_map: false,
+            #[cfg(feature = "serde")]
+            emit_ir_json: false,
+            pure_annotation: false,
+            minify: false,
             need_reactivity: true,
+            trim_static_attrs: false,
+            parse_static_style: false,
+            max_depth: 128,
+            report_depth: false,
+            warn_constant: false,
+            max_handler_tokens: None,
+            max_dynamic_props: None,
+            inject_key: true,
+            force_fragment_root: false,
+            global_allow_list: FxHashSet::default(),
+            deny_helpers: HelperCollector::new(),
             error_handler: Rc::new(NoopErrorHandler),
+            filename: None,
         }
     }
 }
@@ -160,19 +294,25 @@ impl CompileOption {
         ScanOption {
             delimiters: self.delimiters.clone(),
             get_text_mode: self.get_text_mode,
+            raw_elements: self.raw_elements.clone(),
         }
     }
     pub fn parsing(&self) -> ParseOption {
         ParseOption {
+            parse_mode: self.parse_mode,
             whitespace: self.whitespace.clone(),
             preserve_comment: self.preserve_comments.unwrap_or(self.is_dev),
             get_namespace: self.get_namespace,
             get_text_mode: self.get_text_mode,
+            raw_elements: self.raw_elements.clone(),
+            delimiters: self.delimiters.clone(),
             is_native_element: self.is_native_tag,
             is_void_tag: self.is_void_tag,
             is_pre_tag: self.is_pre_tag,
             get_builtin_component: self.get_builtin_component,
             is_custom_element: self.is_custom_element,
+            max_depth: self.max_depth,
+            compat_v_is: self.compat_v_is,
         }
     }
     pub fn converting(&self) -> ConvertOption {
@@ -181,6 +321,26 @@ impl CompileOption {
             is_dev: self.is_dev,
             directive_converters: self.directive_converters.clone(),
             need_reactivity: self.need_reactivity,
+            trim_static_attrs: self.trim_static_attrs,
+            parse_static_style: self.parse_static_style,
+            component_resolver: self.component_resolver,
+            transform_asset_url: match self.mode {
+                ScriptMode::Module { .. } => self.transform_asset_url,
+                ScriptMode::Function { .. } => None,
+            },
+            compat_v_is: self.compat_v_is,
+            max_handler_tokens: self.max_handler_tokens,
+            lowercase_attrs: matches!(self.parse_mode, ParseMode::Html),
+            is_custom_element: if self.preserve_custom_element_attr_case {
+                self.is_custom_element
+            } else {
+                no
+            },
+            is_model_custom_element: if self.custom_element_v_model {
+                self.is_custom_element
+            } else {
+                no
+            },
         }
     }
     pub fn transforming(&self) -> TransformOption {
@@ -193,6 +353,8 @@ impl CompileOption {
         TransformOption {
             prefix_identifier: prefix,
             is_dev: self.is_dev,
+            report_depth: self.report_depth,
+            inject_key: self.inject_key,
         }
     }
     pub fn codegen(&self) -> CodeGenerateOption {
@@ -201,6 +363,13 @@ impl CompileOption {
             mode: self.mode.clone(),
             source_map: self.source_map,
             helper_strs: self.helper_strs,
+            pure_annotation: self.pure_annotation,
+            minify: self.minify,
+            hoist_prefix: self.hoist_prefix.clone(),
+            helper_prefix: self.helper_prefix.clone(),
+            helper_imports: true,
+            render_name: "render".into(),
+            force_fragment_root: self.force_fragment_root,
         }
     }
 }
@@ -227,6 +396,44 @@ pub trait TemplateCompiler<'a> {
     }
 }
 
+/// Metrics of a compiled template, e.g. for profiling template complexity in CI.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CompileStats {
+    /// runtime helpers used
+    pub helpers: usize,
+    /// components resolved by resolveComponent
+    pub components: usize,
+    /// directives resolved by resolveDirective
+    pub directives: usize,
+    /// hoisted vnodes/props
+    pub hoists: usize,
+    /// `_cache` slots used
+    pub cache_slots: usize,
+    /// nodes generated with a patch flag
+    pub patched_nodes: usize,
+}
+
+impl CompileStats {
+    pub fn new(root: &BaseRoot) -> Self {
+        let scope = &root.top_scope;
+        Self {
+            helpers: scope.helpers.len(),
+            components: scope.components.len(),
+            directives: scope.directives.len(),
+            hoists: scope.hoists.len(),
+            cache_slots: scope.cache_slots,
+            patched_nodes: scope.patched_nodes,
+        }
+    }
+}
+
+pub struct CompileResult<W> {
+    pub output: W,
+    pub stats: CompileStats,
+    /// runtime helpers as (helper_name, imported_as), e.g. ("openBlock", "_openBlock")
+    pub imports: Vec<(&'static str, String)>,
+}
+
 pub struct BaseCompiler<'a, P, W>
 where
     W: io::Write,
@@ -248,8 +455,12 @@ where
     pub fn new(
         writer: fn() -> W,
         passes: fn(&'a SFCInfo<'a>, &CompileOption) -> P,
-        option: CompileOption,
+        mut option: CompileOption,
     ) -> Self {
+        if let Some(filename) = &option.filename {
+            let inner = option.error_handler.clone();
+            option.error_handler = Rc::new(FilenameErrorHandler::new(filename, inner));
+        }
         Self {
             writer,
             passes,
@@ -264,6 +475,29 @@ where
         let option = self.option.converting();
         BaseConverter::new(eh, option)
     }
+    /// Same as compile but also reports stats and helper imports of the transformed IR.
+    pub fn compile_with_stats(
+        &self,
+        source: &'a str,
+        info: &'a SFCInfo<'a>,
+    ) -> io::Result<CompileResult<W>> {
+        let tokens = self.scan(source);
+        let ast = self.parse(tokens);
+        let mut ir = self.convert(ast, info);
+        self.transform(&mut ir, info);
+        let stats = CompileStats::new(&ir);
+        let option = &self.option;
+        let imports = ir
+            .top_scope
+            .helpers
+            .imports(option.helper_strs, &option.helper_prefix);
+        let output = self.generate(ir, info)?;
+        Ok(CompileResult {
+            output,
+            stats,
+            imports,
+        })
+    }
 }
 
 impl<'a, P, W> TemplateCompiler<'a> for BaseCompiler<'a, P, W>
@@ -287,10 +521,17 @@ where
     }
     fn transform(&self, ir: &mut Self::IR, info: Self::Info) {
         let pass = (self.passes)(info, &self.option);
-        BaseTransformer::transform(ir, pass)
+        let eh = self.get_error_handler();
+        let limit = DepthLimit::new(self.option.max_depth, eh);
+        BaseTransformer::transform_with_limit(ir, pass, &limit)
     }
     fn generate(&self, ir: Self::IR, sfc_info: Self::Info) -> Self::Output {
         let mut writer = (self.writer)();
+        #[cfg(feature = "serde")]
+        if self.option.emit_ir_json {
+            serde_json::to_writer(&mut writer, &ir)?;
+            return Ok(writer);
+        }
         let option = self.option.codegen();
         let generator = CodeGen::new(option);
         let gen_info = CodeGenInfo {
@@ -310,24 +551,35 @@ pub fn get_base_passes<'a>(
     opt: &CompileOption,
 ) -> impl CorePass<BaseInfo<'a>> {
     use crate::chain;
-    let prefix_identifier = opt.transforming().prefix_identifier;
+    let transform_option = opt.transforming();
+    // inline mode must prefix identifiers to resolve setup bindings
+    let prefix_identifier = transform_option.prefix_identifier || sfc_info.inline;
     let shared = chain![
         SlotFlagMarker,
         ExpressionProcessor {
             prefix_identifier,
             sfc_info,
             err_handle: opt.error_handler.clone(),
+            global_allow_list: opt.global_allow_list.clone(),
         },
     ];
     chain![
+        DepthTracker::new(transform_option.report_depth),
+        TempLifter,
+        ConstantWarner::new(opt.warn_constant, opt.error_handler.clone()),
         TextOptimizer,
-        EntityCollector::default(),
-        PatchFlagMarker,
+        DynamicPropsWarner::new(opt.max_dynamic_props, opt.error_handler.clone()),
+        EntityCollector::new(opt.force_fragment_root).with_deny_list(
+            opt.deny_helpers.clone(),
+            opt.helper_strs,
+            opt.error_handler.clone(),
+        ),
+        PatchFlagMarker::new(transform_option.inject_key),
         SharedInfoPasses {
             passes: shared,
             shared_info: Scope::default(),
             pd: PhantomData,
         },
-        HoistStatic::default(),
+        HoistStatic::new(opt.hoist_sink.clone()),
     ]
 }
@@ -0,0 +1,201 @@
+//! Compile many templates into one output, e.g. for bundler plugins.
+//! Helper imports are deduplicated across templates and left to the
+//! caller to emit once, so each render code has no import block.
+
+use super::{BaseCompiler, TemplateCompiler};
+use crate::{
+    codegen::{CodeGen, CodeGenInfo, CodeGenerator},
+    converter::BaseConvertInfo as BaseInfo,
+    flags::HelperCollector,
+    transformer::CorePass,
+    SFCInfo,
+};
+use std::io;
+
+pub struct BatchResult {
+    /// (helper_name, imported_as) pairs used by any template, each once
+    pub imports: Vec<(&'static str, String)>,
+    /// render code of each template in the input order
+    pub renders: Vec<String>,
+}
+
+impl<'a, P> BaseCompiler<'a, P, Vec<u8>>
+where
+    P: CorePass<BaseInfo<'a>>,
+{
+    /// Compile templates that share one runtime import block.
+    /// Hoists of the nth template are prefixed by `{hoist_prefix}{n}_`
+    /// so they do not clash in the same output. With `hoist_sink`, a hoist
+    /// identical to an earlier template's is referenced instead of declared,
+    /// so renders must be emitted in order in the same scope.
+    /// The nth render function is named `render{n}`. Module mode exports it
+    /// while function mode leaves returning the renders to the caller.
+    pub fn compile_many(
+        &self,
+        sources: &[&'a str],
+        info: &'a SFCInfo<'a>,
+    ) -> io::Result<BatchResult> {
+        let mut helpers = HelperCollector::new();
+        let mut renders = Vec::with_capacity(sources.len());
+        let sink = self.option.hoist_sink.as_deref();
+        if let Some(sink) = sink {
+            sink.clear();
+        }
+        for (i, &source) in sources.iter().enumerate() {
+            let hoist_prefix = format!("{}{}_", self.option.hoist_prefix, i);
+            if let Some(sink) = sink {
+                sink.start_template(&hoist_prefix);
+            }
+            let tokens = self.scan(source);
+            let ast = self.parse(tokens);
+            let mut ir = self.convert(ast, info);
+            self.transform(&mut ir, info);
+            for rh in ir.top_scope.helpers.clone() {
+                helpers.collect(rh);
+            }
+            let mut option = self.option.codegen();
+            option.hoist_prefix = hoist_prefix;
+            option.helper_imports = false;
+            option.render_name = format!("render{}", i);
+            let mut writer = Vec::new();
+            let gen_info = CodeGenInfo {
+                writer: &mut writer,
+                sfc_info: info,
+            };
+            CodeGen::new(option).generate(ir, gen_info)?;
+            let code = String::from_utf8(writer)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            renders.push(code);
+        }
+        if let Some(sink) = sink {
+            sink.end_batch();
+        }
+        Ok(BatchResult {
+            imports: helpers.imports(self.option.helper_strs, &self.option.helper_prefix),
+            renders,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::codegen::ScriptMode;
+    use crate::compiler::{get_base_passes, CompileOption};
+
+    #[test]
+    fn test_compile_many() {
+        let option = CompileOption {
+            mode: ScriptMode::Module {
+                runtime_module_name: "vue".into(),
+            },
+            ..Default::default()
+        };
+        let sfc_info = SFCInfo::default();
+        let compiler = BaseCompiler::new(Vec::new, get_base_passes, option);
+        let sources = ["<div><p>a</p>{{b}}</div>", "<p v-if='c'><i>d</i>{{e}}</p>"];
+        let ret = compiler.compile_many(&sources, &sfc_info).unwrap();
+        let mut names: Vec<_> = ret.imports.iter().map(|i| i.0).collect();
+        names.sort_unstable();
+        let expected = [
+            "createCommentVNode",
+            "createElementBlock",
+            "createElementVNode",
+            "createTextVNode",
+            "openBlock",
+            "toDisplayString",
+        ];
+        assert_eq!(names, expected);
+        assert_eq!(ret.renders.len(), 2);
+        for code in &ret.renders {
+            assert!(!code.contains("import "), "{}", code);
+        }
+        assert!(ret.renders[0].contains("const _hoisted_0_0 ="));
+        assert!(ret.renders[1].contains("const _hoisted_1_0 ="));
+        let module = join_module(&ret);
+        assert!(module.contains("export function render0("), "{}", module);
+        assert!(module.contains("export function render1("), "{}", module);
+        assert_valid_module(&module);
+    }
+
+    #[test]
+    fn test_compile_many_function_mode() {
+        let option = CompileOption::default();
+        let sfc_info = SFCInfo::default();
+        let compiler = BaseCompiler::new(Vec::new, get_base_passes, option);
+        let ret = compiler.compile_many(&["<p/>", "<i/>"], &sfc_info).unwrap();
+        for (i, code) in ret.renders.iter().enumerate() {
+            assert!(!code.contains("return function"), "{}", code);
+            assert!(code.contains(&format!("function render{}(", i)), "{}", code);
+        }
+    }
+
+    fn join_module(ret: &BatchResult) -> String {
+        let imports: Vec<_> = ret
+            .imports
+            .iter()
+            .map(|(name, local)| format!("{} as {}", name, local))
+            .collect();
+        let mut module = format!("import {{ {} }} from \"vue\"\n", imports.join(", "));
+        for render in &ret.renders {
+            module.push_str(render);
+            module.push('\n');
+        }
+        module
+    }
+
+    // the module parses, declares each top level name once
+    // and declares every hoist it references.
+    fn assert_valid_module(module: &str) {
+        let parsed = rslint_parser::parse_module(module, 0);
+        assert!(parsed.errors().is_empty(), "{}", module);
+        let mut declared = vec![];
+        for line in module.lines() {
+            let line = line.trim_start_matches("export ");
+            let name = line
+                .strip_prefix("const ")
+                .or_else(|| line.strip_prefix("function "))
+                .and_then(|l| l.split([' ', '(']).next());
+            if let Some(name) = name {
+                assert!(!declared.contains(&name), "{} redeclared: {}", name, module);
+                declared.push(name);
+            }
+        }
+        for (i, _) in module.match_indices("_hoisted_") {
+            let name = module[i..]
+                .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .next()
+                .unwrap();
+            assert!(declared.contains(&name), "{} undeclared: {}", name, module);
+        }
+    }
+
+    #[test]
+    fn test_shared_hoists() {
+        use crate::transformer::hoist_static::HoistSink;
+        use std::rc::Rc;
+        let option = CompileOption {
+            hoist_sink: Some(Rc::new(HoistSink::new())),
+            mode: ScriptMode::Module {
+                runtime_module_name: "vue".into(),
+            },
+            ..Default::default()
+        };
+        let sfc_info = SFCInfo::default();
+        let compiler = BaseCompiler::new(Vec::new, get_base_passes, option);
+        let sources = ["<div><b>x</b>{{a}}</div>", "<p><b>x</b><i>y</i>{{b}}</p>"];
+        let ret = compiler.compile_many(&sources, &sfc_info).unwrap();
+        let (first, second) = (&ret.renders[0], &ret.renders[1]);
+        assert!(first.contains("const _hoisted_0_0 = "), "{}", first);
+        assert!(!second.contains("const _hoisted_1_0 = "), "{}", second);
+        assert!(second.contains("const _hoisted_1_1 = "), "{}", second);
+        assert!(second.contains("_hoisted_0_0,"), "{}", second);
+        // shared hoists resolve when renders are joined in order
+        assert_valid_module(&join_module(&ret));
+        // single compile keeps its own hoists
+        let code = compiler.compile(sources[1], &sfc_info).unwrap();
+        let code = String::from_utf8(code).unwrap();
+        assert!(code.contains("const _hoisted_0 = "), "{}", code);
+        assert!(code.contains("const _hoisted_1 = "), "{}", code);
+    }
+}
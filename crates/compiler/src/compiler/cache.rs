@@ -0,0 +1,390 @@
+//! Memoize compiled code for unchanged templates, e.g. in a dev server.
+//! Results are keyed by the source, output-affecting options and passes.
+//! Diagnostics are not cached: errors and warnings, e.g. ForbiddenHelper,
+//! are only reported to the error handler on cache miss.
+
+use super::{BaseCompiler, CompileOption, TemplateCompiler};
+use crate::{converter::BaseConvertInfo as BaseInfo, transformer::CorePass};
+
+use rustc_hash::{FxHashMap, FxHasher};
+use std::{
+    collections::VecDeque,
+    hash::{Hash, Hasher},
+    io,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, RwLock,
+    },
+};
+
+/// Cheap to clone compilation output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CachedOutput {
+    pub code: Arc<str>,
+    /// hash of source and options that produced the code
+    pub key: u64,
+}
+
+/// Everything that determines the output. Entries are looked up by hash
+/// and compared by fingerprint so a hash collision is a cache miss.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CacheKey {
+    hash: u64,
+    fingerprint: Arc<[u8]>,
+}
+
+impl CacheKey {
+    /// `passes` is the address of the pass factory used to compile.
+    pub fn new(source: &str, option: &CompileOption, passes: usize) -> Self {
+        let mut fingerprint = Fingerprint::default();
+        source.hash(&mut fingerprint);
+        hash_option(option, &mut fingerprint);
+        passes.hash(&mut fingerprint);
+        Self {
+            hash: fingerprint.finish(),
+            fingerprint: fingerprint.0.into(),
+        }
+    }
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+}
+
+// records the bytes fed by Hash impls, which are unambiguous
+// so equal fingerprints mean equal inputs.
+#[derive(Default)]
+struct Fingerprint(Vec<u8>);
+impl Hasher for Fingerprint {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+    fn finish(&self) -> u64 {
+        let mut h = FxHasher::default();
+        h.write(&self.0);
+        h.finish()
+    }
+}
+
+struct Entry {
+    fingerprint: Arc<[u8]>,
+    output: CachedOutput,
+}
+
+#[derive(Default)]
+struct Entries {
+    map: FxHashMap<u64, Entry>,
+    /// insertion order for eviction
+    order: VecDeque<u64>,
+}
+
+/// A thread safe compile cache. Share it by `Arc<CompileCache>`.
+/// The oldest entry is evicted once the capacity is reached.
+pub struct CompileCache {
+    entries: RwLock<Entries>,
+    capacity: usize,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+impl Default for CompileCache {
+    fn default() -> Self {
+        Self::with_capacity(1024)
+    }
+}
+
+impl CompileCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Default::default(),
+            capacity: capacity.max(1),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        }
+    }
+    pub fn get(&self, key: &CacheKey) -> Option<CachedOutput> {
+        let entries = self.entries.read().expect("cache lock poisoned");
+        let ret = entries
+            .map
+            .get(&key.hash)
+            .filter(|e| e.fingerprint == key.fingerprint)
+            .map(|e| e.output.clone());
+        if ret.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        }
+        ret
+    }
+    pub fn get_or_compile<F>(&self, key: CacheKey, compile: F) -> io::Result<CachedOutput>
+    where
+        F: FnOnce() -> io::Result<Vec<u8>>,
+    {
+        if let Some(cached) = self.get(&key) {
+            return Ok(cached);
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let code = String::from_utf8(compile()?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let output = CachedOutput {
+            code: code.into(),
+            key: key.hash,
+        };
+        let entry = Entry {
+            fingerprint: key.fingerprint,
+            output: output.clone(),
+        };
+        let mut entries = self.entries.write().expect("cache lock poisoned");
+        // a colliding entry is replaced in place
+        if entries.map.insert(key.hash, entry).is_none() {
+            entries.order.push_back(key.hash);
+            if entries.order.len() > self.capacity {
+                let oldest = entries.order.pop_front().expect("order is not empty");
+                entries.map.remove(&oldest);
+            }
+        }
+        Ok(output)
+    }
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+    pub fn misses(&self) -> usize {
+        self.misses.load(Ordering::Relaxed)
+    }
+    pub fn len(&self) -> usize {
+        self.entries.read().expect("cache lock poisoned").map.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    pub fn clear(&self) {
+        let mut entries = self.entries.write().expect("cache lock poisoned");
+        entries.map.clear();
+        entries.order.clear();
+    }
+}
+
+/// Hash every option that can change the output.
+/// Function options are hashed by address.
+fn hash_option<H: Hasher>(option: &CompileOption, h: &mut H) {
+    // destructure so new options will not be silently left out
+    let CompileOption {
+        is_native_tag,
+        is_void_tag,
+        is_pre_tag,
+        get_builtin_component,
+        is_custom_element,
+        get_namespace,
+        get_text_mode,
+        delimiters,
+        raw_elements,
+        whitespace,
+        parse_mode,
+        helper_strs,
+        preserve_comments,
+        is_dev,
+        directive_converters,
+        component_resolver,
+        transform_asset_url,
+        compat_v_is,
+        preserve_custom_element_attr_case,
+        custom_element_v_model,
+        hoist_static,
+        hoist_prefix,
+        hoist_sink,
+        helper_prefix,
+        cache_handlers,
+        mode,
+        source_map,
+        #[cfg(feature = "serde")]
+        emit_ir_json,
+        pure_annotation,
+        minify,
+        need_reactivity,
+        trim_static_attrs,
+        parse_static_style,
+        max_depth,
+        report_depth,
+        warn_constant,
+        max_handler_tokens,
+        max_dynamic_props,
+        inject_key,
+        force_fragment_root,
+        global_allow_list,
+        deny_helpers,
+        error_handler: _,
+        filename: _,
+    } = option;
+    let fns = [
+        *is_native_tag as usize,
+        *is_void_tag as usize,
+        *is_pre_tag as usize,
+        *get_builtin_component as usize,
+        *is_custom_element as usize,
+        *get_namespace as usize,
+        *get_text_mode as usize,
+    ];
+    fns.hash(h);
+    delimiters.hash(h);
+    raw_elements.hash(h);
+    whitespace.hash(h);
+    parse_mode.hash(h);
+    helper_strs.hash(h);
+    preserve_comments.hash(h);
+    is_dev.hash(h);
+    let mut dirs: Vec<_> = directive_converters
+        .iter()
+        .map(|(k, f)| (*k, *f as usize))
+        .collect();
+    dirs.sort_unstable();
+    dirs.hash(h);
+    component_resolver.map(|f| f as usize).hash(h);
+    transform_asset_url.map(|f| f as usize).hash(h);
+    compat_v_is.hash(h);
+    preserve_custom_element_attr_case.hash(h);
+    custom_element_v_model.hash(h);
+    hoist_static.hash(h);
+    hoist_prefix.hash(h);
+    hoist_sink.is_some().hash(h);
+    helper_prefix.hash(h);
+    cache_handlers.hash(h);
+    mode.hash(h);
+    source_map.hash(h);
+    #[cfg(feature = "serde")]
+    emit_ir_json.hash(h);
+    pure_annotation.hash(h);
+    minify.hash(h);
+    need_reactivity.hash(h);
+    trim_static_attrs.hash(h);
+    parse_static_style.hash(h);
+    max_depth.hash(h);
+    report_depth.hash(h);
+    warn_constant.hash(h);
+    max_handler_tokens.hash(h);
+    max_dynamic_props.hash(h);
+    inject_key.hash(h);
+    force_fragment_root.hash(h);
+    deny_helpers.hash(h);
+    let mut globals: Vec<_> = global_allow_list.iter().collect();
+    globals.sort_unstable();
+    globals.hash(h);
+}
+
+impl<'a, P> BaseCompiler<'a, P, Vec<u8>>
+where
+    P: CorePass<BaseInfo<'a>>,
+{
+    /// Same as compile but reuses the output in cache if any.
+    /// A cache hit reports no diagnostics to the error handler.
+    /// NB: SFCInfo is not part of the key. Use separate caches
+    /// for templates compiled with different SFCInfo.
+    pub fn compile_cached(
+        &self,
+        cache: &CompileCache,
+        source: &'a str,
+        info: <Self as TemplateCompiler<'a>>::Info,
+    ) -> io::Result<CachedOutput> {
+        let key = CacheKey::new(source, &self.option, self.passes as usize);
+        cache.get_or_compile(key, || self.compile(source, info))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::compiler::get_base_passes;
+    use crate::SFCInfo;
+
+    fn compile(cache: &CompileCache, s: &str, option: CompileOption) -> CachedOutput {
+        let sfc_info = SFCInfo::default();
+        let compiler = BaseCompiler::new(Vec::new, get_base_passes, option);
+        compiler.compile_cached(cache, s, &sfc_info).unwrap()
+    }
+
+    #[test]
+    fn test_cache_hit() {
+        let cache = CompileCache::new();
+        let a = compile(&cache, "<p>{{a}}</p>", CompileOption::default());
+        let b = compile(&cache, "<p>{{a}}</p>", CompileOption::default());
+        assert_eq!(cache.misses(), 1);
+        assert_eq!(cache.hits(), 1);
+        assert!(Arc::ptr_eq(&a.code, &b.code));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_cache_miss_on_option_change() {
+        let cache = CompileCache::new();
+        let a = compile(&cache, "<p>{{a}}</p>", CompileOption::default());
+        let option = CompileOption {
+            is_dev: false,
+            ..Default::default()
+        };
+        let b = compile(&cache, "<p>{{a}}</p>", option);
+        assert_eq!(cache.misses(), 2);
+        assert_eq!(cache.hits(), 0);
+        assert_ne!(a.key, b.key);
+        compile(&cache, "<p>{{b}}</p>", CompileOption::default());
+        assert_eq!(cache.misses(), 3);
+    }
+
+    #[test]
+    fn test_cache_miss_on_passes_change() {
+        use crate::{chain, transformer::optimize_text::TextOptimizer};
+        fn text_pass<'a>(
+            info: &'a SFCInfo<'a>,
+            option: &CompileOption,
+        ) -> impl CorePass<BaseInfo<'a>> {
+            chain![get_base_passes(info, option), TextOptimizer]
+        }
+        let cache = CompileCache::new();
+        let sfc_info = SFCInfo::default();
+        compile(&cache, "<p>{{a}}</p>", CompileOption::default());
+        let compiler = BaseCompiler::new(Vec::new, text_pass, CompileOption::default());
+        compiler
+            .compile_cached(&cache, "<p>{{a}}</p>", &sfc_info)
+            .unwrap();
+        assert_eq!(cache.misses(), 2);
+        assert_eq!(cache.hits(), 0);
+    }
+
+    #[test]
+    fn test_hash_collision() {
+        let cache = CompileCache::new();
+        let key = |fingerprint: &[u8]| CacheKey {
+            hash: 42,
+            fingerprint: fingerprint.into(),
+        };
+        let a = cache
+            .get_or_compile(key(b"a"), || Ok(b"a".to_vec()))
+            .unwrap();
+        let b = cache
+            .get_or_compile(key(b"b"), || Ok(b"b".to_vec()))
+            .unwrap();
+        assert_eq!(&*a.code, "a");
+        assert_eq!(&*b.code, "b");
+        assert_eq!(cache.misses(), 2);
+        assert!(cache.get(&key(b"a")).is_none());
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_capacity() {
+        let cache = CompileCache::with_capacity(2);
+        let option = CompileOption::default();
+        let key = |s| CacheKey::new(s, &option, 0);
+        for s in ["a", "b", "c"] {
+            cache.get_or_compile(key(s), || Ok(s.into())).unwrap();
+        }
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&key("a")).is_none());
+        assert!(cache.get(&key("c")).is_some());
+    }
+
+    #[test]
+    fn test_cache_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<CompileCache>();
+        assert_send_sync::<CachedOutput>();
+    }
+}
@@ -0,0 +1,115 @@
+//! Compile a single binding expression without a template,
+//! e.g. for tools validating `:class` or `:style` contents.
+
+use super::CompileOption;
+use crate::{
+    codegen::{CodeWriter, CoreCodeGenerator},
+    error::{CompilationError, CompilationErrorKind as ErrorKind, VecErrorHandler},
+    flags::HelperCollector,
+    transformer::pass::Scope,
+    ExpressionProcessor, Js, SFCInfo,
+};
+use std::rc::Rc;
+
+/// Prefixes and validates the expression as if it were bound in a template.
+/// Identifiers are always prefixed regardless of the script mode, and
+/// sfc_info's binding metadata decides how each identifier is accessed.
+/// Runtime helpers in the output, e.g. `_unref`, must be in scope of the caller.
+pub fn compile_expression<'a>(
+    expr: &'a str,
+    option: &CompileOption,
+    sfc_info: &SFCInfo<'a>,
+) -> Result<String, Vec<CompilationError>> {
+    if expr.trim().is_empty() {
+        return Err(vec![CompilationError::new(ErrorKind::InvalidExpression)]);
+    }
+    let err_handle = Rc::new(VecErrorHandler::new());
+    let processor = ExpressionProcessor {
+        prefix_identifier: true,
+        sfc_info,
+        err_handle: err_handle.clone(),
+        global_allow_list: option.global_allow_list.clone(),
+    };
+    let mut e = Js::simple(expr);
+    processor.process_expression(&mut e, &mut Scope::default());
+    let errors: Vec<_> = err_handle.error_mut().drain(..).collect();
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    let mut helpers = HelperCollector::new();
+    collect_helpers(&e, &mut helpers);
+    let mut output = Vec::new();
+    let codegen_option = Rc::new(option.codegen());
+    let mut writer = CodeWriter::new(&mut output, codegen_option, sfc_info);
+    writer.set_helpers(helpers);
+    writer
+        .generate_js_expr(e)
+        .expect("writing to Vec should not fail");
+    Ok(String::from_utf8(output).expect("expression should be valid utf8"))
+}
+
+fn collect_helpers(e: &Js, helpers: &mut HelperCollector) {
+    match e {
+        Js::Call(h, args) => {
+            helpers.collect(*h);
+            args.iter().for_each(|a| collect_helpers(a, helpers));
+        }
+        Js::Symbol(h) => helpers.collect(*h),
+        Js::Compound(v) | Js::Array(v) | Js::FuncCompound { body: v, .. } => {
+            v.iter().for_each(|a| collect_helpers(a, helpers));
+        }
+        Js::Props(ps) => ps.iter().for_each(|(k, v)| {
+            collect_helpers(k, helpers);
+            collect_helpers(v, helpers);
+        }),
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{BindingMetadata, BindingTypes};
+    use rustc_hash::FxHashMap;
+
+    fn compile(expr: &str) -> String {
+        compile_expression(expr, &CompileOption::default(), &SFCInfo::default())
+            .unwrap_or_else(|errors| panic!("{} errors in {}", errors.len(), expr))
+    }
+
+    #[test]
+    fn test_compile_expression() {
+        assert_eq!(compile("count + 1"), "_ctx.count + 1");
+        assert_eq!(
+            compile("{ active: isActive, 'text-red': Math.max(a) }"),
+            "{ active: _ctx.isActive, 'text-red': Math.max(_ctx.a) }"
+        );
+    }
+
+    #[test]
+    fn test_compile_invalid_expression() {
+        for expr in ["a +", "", "   "] {
+            let info = SFCInfo::default();
+            let errors = compile_expression(expr, &CompileOption::default(), &info).unwrap_err();
+            assert_eq!(errors.len(), 1);
+            assert!(matches!(errors[0].kind, ErrorKind::InvalidExpression));
+        }
+    }
+
+    #[test]
+    fn test_compile_with_bindings() {
+        let mut map = FxHashMap::default();
+        map.insert("count", BindingTypes::SetupRef);
+        map.insert("maybe", BindingTypes::SetupMaybeRef);
+        let info = SFCInfo {
+            inline: true,
+            binding_metadata: BindingMetadata::new_setup(map),
+            ..SFCInfo::default()
+        };
+        let compiled = compile_expression("count + maybe", &CompileOption::default(), &info);
+        assert!(matches!(
+            compiled.as_deref(),
+            Ok("count.value + _unref(maybe)")
+        ));
+    }
+}
@@ -1,12 +1,15 @@
-use super::{BaseConversion as BC, CoreConversion, Element, VStr};
+use super::{BaseConversion as BC, CompilationError, CoreConversion, Element, VStr};
 use crate::{
+    error::CompilationErrorKind as ErrorKind,
     flags::{self, PatchFlag, RuntimeHelper},
     ir::{JsExpr as Js, Prop},
     parser::{Directive, ElemProp},
     scanner::Attribute,
+    Namespace, SourceLocation,
     util::{self, is_bind_key, is_component_tag, is_reserved_prop},
 };
 use rustc_hash::{FxHashMap, FxHashSet};
+use std::borrow::Cow;
 use std::iter::IntoIterator;
 use std::mem;
 
@@ -23,7 +26,6 @@ struct PropFlags {
     has_ref: bool,
     has_class_binding: bool,
     has_style_binding: bool,
-    has_hydration_event_binding: bool,
     has_dynamic_keys: bool,
     has_vnode_hook: bool,
 }
@@ -31,10 +33,15 @@ struct PropFlags {
 #[derive(Default)]
 /// collecting props object for vnode call. e.g:
 /// <:prop="val" v-bind="obj"/> becomes {prop: val, ...obj}
+/// Props keep source order. Duplicate class/style/handlers are merged into
+/// the first occurrence. key and ref are plain props and keep their position.
+/// Directive generated props like `onUpdate:modelValue` follow their directive
+/// and injected v-if key is always the first.
 struct PropArgs<'a> {
     /// pending properties, e.g. (prop, val)
     pending_props: Props<'a>,
-    /// merged prop argument, e.g. obj
+    /// merged prop argument, e.g. obj. pending props are flushed before
+    /// each spread so mergeProps arguments keep the source order.
     merge_args: Args<'a>,
 }
 
@@ -83,32 +90,117 @@ where
     }
 }
 
-fn collect_attr<'a>(bc: &BC, e: &Element<'a>, attr: Attribute<'a>, cp: &mut CollectProps<'a>) {
-    let Attribute { name, value, .. } = attr;
-    let val = match value {
+fn collect_attr<'a>(bc: &BC<'a>, e: &Element<'a>, attr: Attribute<'a>, cp: &mut CollectProps<'a>) {
+    let Attribute {
+        name,
+        value,
+        location,
+        ..
+    } = attr;
+    let mut val = match value {
         Some(v) => v.content,
         None => VStr::raw(""),
     };
+    if bc.option.trim_static_attrs && (name == "class" || name == "style") {
+        val.raw = val.raw.trim();
+        if name == "class" {
+            val.compress_whitespace();
+        }
+    }
     // skip dynamic component is
     if name == "is" && (is_component_tag(e.tag_name) || val.starts_with("vue:")) {
         return;
     }
     let mut value_expr = Js::StrLit(val);
+    if name == "style" && bc.option.parse_static_style {
+        value_expr = parse_static_style(bc, val, location);
+    }
+    if let (Some(transform), false) = (bc.option.transform_asset_url, e.is_component()) {
+        if let Some(path) = transform(e.tag_name, name, val.raw) {
+            value_expr = bc.add_import(path);
+        }
+    }
     if name == "ref" {
         cp.prop_flags.has_ref = true;
         if bc.sfc_info.inline && !val.is_empty() {
             value_expr = process_inline_ref(val);
         }
     }
+    let mut key = VStr::raw(name);
+    if should_lowercase_attr(bc, e, name) {
+        key.lowercase();
+    }
     cp.prop_args
         .pending_props
-        .push((Js::str_lit(name), value_expr));
+        .push((Js::StrLit(key), value_expr));
+}
+
+// browsers keep adjusted casing for foreign attributes, e.g. svg viewBox
+fn should_lowercase_attr(bc: &BC, e: &Element, name: &str) -> bool {
+    let opt = &bc.option;
+    opt.lowercase_attrs
+        && e.namespace == Namespace::Html
+        && !e.is_component()
+        && !(opt.is_custom_element)(e.tag_name)
+        && name.bytes().any(|b| b.is_ascii_uppercase())
+}
+
+/// e.g. `color: red; font-size: 14px` => `{ color: "red", fontSize: "14px" }`
+fn parse_static_style<'a>(bc: &BC<'a>, val: VStr<'a>, loc: SourceLocation) -> Js<'a> {
+    // comments cannot be sliced out of the source, leave it to runtime
+    if val.raw.contains("/*") {
+        return Js::StrLit(val);
+    }
+    let mut props = vec![];
+    let mut malformed = false;
+    for decl in split_style_declarations(val.raw) {
+        let decl = decl.trim();
+        if decl.is_empty() {
+            continue;
+        }
+        let (prop, value) = match decl.split_once(':') {
+            Some((p, v)) if !p.trim().is_empty() && !v.trim().is_empty() => (p.trim(), v.trim()),
+            _ => {
+                malformed = true;
+                continue;
+            }
+        };
+        let mut key = VStr::raw(prop);
+        // custom properties are case sensitive
+        if !prop.starts_with("--") {
+            key.camelize();
+        }
+        let value = VStr {
+            raw: value,
+            ops: val.ops,
+        };
+        props.push((Js::StrLit(key), Js::StrLit(value)));
+    }
+    if malformed {
+        let error = CompilationError::new(ErrorKind::InvalidStyleDeclaration).with_location(loc);
+        bc.emit_error(error);
+    }
+    Js::Props(props)
+}
+
+/// split by `;` not in parens, e.g. `background: url(data:a;base64,b)`
+fn split_style_declarations(s: &str) -> impl Iterator<Item = &str> {
+    let mut depth = 0usize;
+    s.split(move |c| {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ';' => return depth == 0,
+            _ => (),
+        }
+        false
+    })
 }
 
 #[inline]
 fn is_pre_convert_dir(s: &str) -> bool {
     match s.len() {
-        2 => s == "if" || s == "is",
+        2 => s == "if",
         4 => ["slot", "memo", "once"].contains(&s),
         _ => s == "for",
     }
@@ -127,6 +219,9 @@ fn collect_dir<'a>(
     if is_pre_convert_dir(name) {
         return;
     }
+    if name == "is" && bc.option.compat_v_is {
+        return; // resolved as dynamic component
+    }
     if is_bind_key(argument, "is") && is_component_tag(e.tag_name) {
         return; // skip <component :is="c"/>
     }
@@ -140,7 +235,8 @@ fn collect_dir<'a>(
         Err(true) => cp.runtime_dirs.push((dir, None)),
         Err(false) => (),
     }
-    if let Js::Props(props) = value {
+    if let Js::Props(mut props) = value {
+        props.retain(|p| !is_empty_class_style(p));
         props.iter().for_each(|p| analyze_patch_flag(p, cp));
         cp.prop_args.pending_props.extend(props);
         return;
@@ -148,9 +244,29 @@ fn collect_dir<'a>(
     flush_pending_props(&mut cp.prop_args);
     // if dir returns an object, dynamic key must be true
     cp.prop_flags.has_dynamic_keys = true;
+    let value = if cp.prop_flags.is_component && name == "bind" {
+        // component props may be mutated by child, guard reactive source object
+        Js::Call(RuntimeHelper::GUARD_REACTIVE_PROPS, vec![value])
+    } else {
+        value
+    };
     cp.prop_args.merge_args.push(value);
 }
 
+// class/style bound to empty literals like :class="''" need no patch.
+// statically authored class="" is kept since it is an explicit attribute.
+fn is_empty_class_style(p: &Prop) -> bool {
+    let (key, val) = match p {
+        (Js::StrLit(k), Js::Simple(v, _)) => (k, v),
+        _ => return false,
+    };
+    if key.raw != "class" && key.raw != "style" {
+        return false;
+    }
+    let empty_literals = ["''", "\"\"", "``", "null", "undefined", "[]", "{}"];
+    empty_literals.contains(&val.raw.trim())
+}
+
 fn flush_pending_props(prop_args: &mut PropArgs) {
     // flush existing props to an object
     if prop_args.pending_props.is_empty() {
@@ -170,33 +286,42 @@ fn dedupe_properties(props: Props) -> Props {
     let mut ret = vec![];
     for (key, val) in props {
         let name = match &key {
-            Js::StrLit(name) => name,
+            Js::StrLit(name) => dedupe_key(*name),
             _ => {
                 ret.push((key, val));
                 continue;
             }
         };
-        if let Some(&i) = known_props.get(name) {
-            if util::is_mergeable_prop(name) {
+        if let Some(&i) = known_props.get(&name) {
+            if util::is_mergeable_prop(&name) {
                 merge_as_array(&mut ret[i], val);
             }
             // TODO: should remove by parser
         } else {
-            known_props.insert(*name, ret.len());
+            known_props.insert(name, ret.len());
             ret.push((key, val));
         }
     }
     ret
 }
 
+// compare rendered names since component prop keys may be camelized.
+// plain keys are borrowed so only keys with string ops allocate.
+fn dedupe_key(name: VStr) -> Cow<str> {
+    if name.ops.is_empty() {
+        Cow::Borrowed(name.raw)
+    } else {
+        Cow::Owned(name.into_string())
+    }
+}
+
 fn merge_as_array<'a>(existing: &mut Prop<'a>, incoming: Js<'a>) {
     let val = &mut existing.1;
     if let Js::Array(arr) = val {
         arr.push(incoming);
     } else {
         let v = mem::replace(val, Js::Src(""));
-        let mut arr = Js::Array(vec![v]);
-        mem::swap(val, &mut arr);
+        *val = Js::Array(vec![v, incoming]);
     }
 }
 
@@ -208,7 +333,15 @@ fn compute_prop_expr(mut prop_args: PropArgs) -> Option<Js> {
     } = prop_args;
     debug_assert!(pending_props.is_empty());
     if merge_args.len() <= 1 {
-        merge_args.into_iter().next()
+        let arg = merge_args.into_iter().next()?;
+        // single guarded spread still needs class/style normalization.
+        // mergeProps normalizes them in other cases.
+        Some(match arg {
+            Js::Call(RuntimeHelper::GUARD_REACTIVE_PROPS, _) => {
+                Js::Call(RuntimeHelper::NORMALIZE_PROPS, vec![arg])
+            }
+            arg => arg,
+        })
     } else {
         Some(Js::Call(RuntimeHelper::MERGE_PROPS, merge_args))
     }
@@ -221,27 +354,25 @@ fn analyze_patch_flag<'a>(p: &Prop<'a>, cp: &mut CollectProps<'a>) {
         (Js::StrLit(k), val) => (k, val),
         _ => return flags.has_dynamic_keys = true,
     };
+    // HYDRATE_EVENTS is marked in PatchFlagMarker
     let is_event_handler = VStr::is_handler(name);
-    if !is_component &&
-        is_event_handler &&
-        // omit click because hydration gives click fast path
-        !name.raw.eq_ignore_ascii_case("click") &&
-        name.raw != "onUpdate:modelValue" && // omit v-model
-        !is_reserved_prop(name)
-    // vnode hooks
-    {
-        flags.has_hydration_event_binding = true;
-    }
     if is_event_handler && is_reserved_prop(name) {
         flags.has_vnode_hook = true;
     }
     if val.static_level() > flags::StaticLevel::NotStatic {
         return;
     }
+    // .class is a DOM property, not the class binding
+    if VStr::is_prop_key(name) {
+        cp.dynamic_props.insert(*name);
+        return;
+    }
     match name.raw {
         "ref" => flags.has_ref = true,
         "class" => flags.has_class_binding = true,
         "style" => flags.has_style_binding = true,
+        // key is diffed by runtime as part of props argument.
+        // createVNode has no separate key param, so it stays in props.
         "key" => (),
         _ => {
             cp.dynamic_props.insert(*name);
@@ -272,12 +403,243 @@ fn build_patch_flag<'a>(
     if !dynamic_names.is_empty() {
         patch_flag |= PatchFlag::PROPS;
     }
-    if f.has_hydration_event_binding {
-        patch_flag |= PatchFlag::HYDRATE_EVENTS;
-    }
-    let no_prop_patch = patch_flag == PatchFlag::empty() || patch_flag == PatchFlag::HYDRATE_EVENTS;
-    if no_prop_patch && (f.has_ref || f.has_vnode_hook || !runtime_dirs.is_empty()) {
+    if patch_flag.is_empty() && (f.has_ref || f.has_vnode_hook || !runtime_dirs.is_empty()) {
         patch_flag |= PatchFlag::NEED_PATCH;
     }
     patch_flag
 }
+
+#[cfg(test)]
+mod test {
+    use super::super::test::base_convert;
+    use super::*;
+    use crate::ir::IRNode;
+
+    fn get_props(s: &str) -> Js<'_> {
+        let mut body = base_convert(s).body;
+        let vn = cast!(body.remove(0), IRNode::VNodeCall);
+        vn.props.expect("should have props")
+    }
+
+    #[test]
+    fn test_component_spread_guarded() {
+        let props = get_props("<comp v-bind='obj'/>");
+        let args = match props {
+            Js::Call(RuntimeHelper::NORMALIZE_PROPS, args) => args,
+            _ => panic!("spread on component should be normalized"),
+        };
+        assert!(matches!(
+            args[0],
+            Js::Call(RuntimeHelper::GUARD_REACTIVE_PROPS, _)
+        ));
+    }
+
+    #[test]
+    fn test_component_spread_merged() {
+        let props = get_props("<comp class='a' v-bind='obj'/>");
+        let args = match props {
+            Js::Call(RuntimeHelper::MERGE_PROPS, args) => args,
+            _ => panic!("mixed props should be merged"),
+        };
+        assert_eq!(args.len(), 2);
+        assert!(matches!(args[0], Js::Props(_)));
+        assert!(matches!(
+            args[1],
+            Js::Call(RuntimeHelper::GUARD_REACTIVE_PROPS, _)
+        ));
+    }
+
+    #[test]
+    fn test_native_spread_unguarded() {
+        let props = get_props("<p v-bind='obj'/>");
+        let expr = cast!(props, Js::Simple);
+        assert_eq!(expr.raw, "obj");
+    }
+
+    fn prop_keys<'a>(arg: &Js<'a>) -> Vec<&'a str> {
+        let ps = cast!(arg, Js::Props);
+        ps.iter().map(|(k, _)| cast!(k, Js::StrLit).raw).collect()
+    }
+
+    #[test]
+    fn test_merge_props_source_order() {
+        // duplicate attributes are rejected by scanner, so only one spread
+        let props = get_props("<p a='1' class='b' v-bind='x' :c='d' e='2' :class='f'/>");
+        let args = match props {
+            Js::Call(RuntimeHelper::MERGE_PROPS, args) => args,
+            _ => panic!("mixed props should be merged"),
+        };
+        assert_eq!(args.len(), 3);
+        assert_eq!(prop_keys(&args[0]), ["a", "class"]);
+        assert_eq!(cast!(&args[1], Js::Simple).raw, "x");
+        assert_eq!(prop_keys(&args[2]), ["c", "e", "class"]);
+    }
+
+    #[test]
+    fn test_class_style_key_ref_order() {
+        use super::super::test::handler_convert;
+        let case =
+            "<p :key='k' class='a' ref='r' :style='s' @click='h' :class='b' style='c' id='i'/>";
+        let mut body = handler_convert(case).body;
+        let vn = cast!(body.remove(0), IRNode::VNodeCall);
+        let ps = cast!(vn.props.expect("should have props"), Js::Props);
+        let keys: Vec<_> = ps
+            .iter()
+            .map(|(k, _)| cast!(k, Js::StrLit).into_string())
+            .collect();
+        assert_eq!(keys, ["key", "class", "ref", "style", "onClick", "id"]);
+        // duplicate class/style are merged into the first occurrence
+        let class = cast!(&ps[1].1, Js::Array);
+        assert_eq!(cast!(&class[0], Js::StrLit).raw, "a");
+        assert_eq!(cast!(&class[1], Js::Simple).raw, "b");
+        let style = cast!(&ps[3].1, Js::Array);
+        assert_eq!(cast!(&style[0], Js::Simple).raw, "s");
+        assert_eq!(cast!(&style[1], Js::StrLit).raw, "c");
+        assert!(vn.patch_flag == PatchFlag::CLASS | PatchFlag::STYLE | PatchFlag::PROPS);
+        let dynamic: Vec<_> = vn.dynamic_props.iter().map(|v| v.into_string()).collect();
+        assert_eq!(dynamic, ["onClick"]);
+    }
+
+    #[test]
+    fn test_merge_component_class() {
+        let props = cast!(
+            get_props("<comp class='a' :class='b' :foo-bar='c' fooBar='d'/>"),
+            Js::Props
+        );
+        assert_eq!(props.len(), 2);
+        let class = cast!(&props[0].1, Js::Array);
+        assert_eq!(cast!(&class[0], Js::StrLit).raw, "a");
+        assert_eq!(cast!(&class[1], Js::Simple).raw, "b");
+        assert_eq!(cast!(&props[1].1, Js::Simple).raw, "c");
+    }
+
+    #[test]
+    fn test_class_style_key_ref_order_with_spread() {
+        let props =
+            get_props("<comp class='a' :key='k' v-bind='o' ref='r' :class='b' :style='s'/>");
+        let args = match props {
+            Js::Call(RuntimeHelper::MERGE_PROPS, args) => args,
+            _ => panic!("mixed props should be merged"),
+        };
+        assert_eq!(args.len(), 3);
+        assert_eq!(prop_keys(&args[0]), ["class", "key"]);
+        assert!(matches!(
+            args[1],
+            Js::Call(RuntimeHelper::GUARD_REACTIVE_PROPS, _)
+        ));
+        // class after spread is left for mergeProps to concat at runtime
+        assert_eq!(prop_keys(&args[2]), ["ref", "class", "style"]);
+    }
+
+    fn convert_vnode(s: &str) -> crate::transformer::BaseVNode<'_> {
+        let mut body = base_convert(s).body;
+        cast!(body.remove(0), IRNode::VNodeCall)
+    }
+
+    #[test]
+    fn test_key_not_dynamic_prop() {
+        let vn = convert_vnode("<div :key='k' :id='i'/>");
+        let props = cast!(vn.props.unwrap(), Js::Props);
+        let key = props
+            .iter()
+            .find(|(k, _)| matches!(k, Js::StrLit(s) if s.raw == "key"));
+        assert_eq!(
+            cast!(&key.expect("key should be in props").1, Js::Simple).raw,
+            "k"
+        );
+        let dynamic: Vec<_> = vn.dynamic_props.iter().map(|v| v.raw).collect();
+        assert_eq!(dynamic, ["id"]);
+        assert!(vn.patch_flag == PatchFlag::PROPS);
+        let vn = convert_vnode("<div :key='k'/>");
+        assert!(vn.dynamic_props.is_empty());
+        assert!(vn.patch_flag.is_empty());
+    }
+
+    #[test]
+    fn test_drop_empty_class_style() {
+        let vn = convert_vnode("<p :class=\"''\" :style='null'/>");
+        assert!(vn.props.is_none());
+        assert!(vn.patch_flag.is_empty());
+        let vn = convert_vnode("<p :class='[]' :id='a'/>");
+        let props = cast!(vn.props.unwrap(), Js::Props);
+        assert_eq!(props.len(), 1);
+        assert!(vn.patch_flag == PatchFlag::PROPS);
+        let vn = convert_vnode("<p :class=\"''\" ref='r'/>");
+        assert!(vn.patch_flag == PatchFlag::NEED_PATCH);
+    }
+
+    #[test]
+    fn test_keep_static_and_dynamic_class() {
+        let vn = convert_vnode("<p class=''/>");
+        assert!(vn.props.is_some());
+        let vn = convert_vnode("<p :class='c'/>");
+        assert!(vn.patch_flag == PatchFlag::CLASS);
+    }
+
+    fn trimmed_attrs(s: &str) -> Vec<String> {
+        use super::super::{BaseConverter, ConvertOption, Converter};
+        use crate::{error::test::TestErrorHandler, parser::test::base_parse, SFCInfo};
+        let option = ConvertOption {
+            trim_static_attrs: true,
+            ..Default::default()
+        };
+        let bc = BaseConverter::new(std::rc::Rc::new(TestErrorHandler), option);
+        let info = SFCInfo::default();
+        let mut body = bc.convert_ir(base_parse(s), &info).body;
+        let vn = cast!(body.remove(0), IRNode::VNodeCall);
+        let props = cast!(vn.props.expect("should have props"), Js::Props);
+        props
+            .into_iter()
+            .map(|(_, v)| cast!(v, Js::StrLit).into_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_static_style() {
+        use super::super::{BaseConverter, ConvertOption, Converter};
+        use crate::{error::VecErrorHandler, parser::test::base_parse, SFCInfo};
+        let option = ConvertOption {
+            parse_static_style: true,
+            ..Default::default()
+        };
+        let eh = std::rc::Rc::new(VecErrorHandler::new());
+        let bc = BaseConverter::new(eh.clone(), option);
+        let info = SFCInfo::default();
+        let s = "<p style='color: red; font-size: 14px; --Main-Color: #fff; bad; background: url(a;b)'/>";
+        let mut body = bc.convert_ir(base_parse(s), &info).body;
+        let vn = cast!(body.remove(0), IRNode::VNodeCall);
+        let props = cast!(vn.props.expect("should have props"), Js::Props);
+        let style = cast!(&props[0].1, Js::Props);
+        let style: Vec<_> = style
+            .iter()
+            .map(|(k, v)| {
+                let k = cast!(k, Js::StrLit).into_string();
+                (k, cast!(v, Js::StrLit).into_string())
+            })
+            .collect();
+        let expected = [
+            ("color", "red"),
+            ("fontSize", "14px"),
+            ("--Main-Color", "#fff"),
+            ("background", "url(a;b)"),
+        ];
+        let expected: Vec<_> = expected
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        assert_eq!(style, expected);
+        let errors = eh.errors();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0].kind, ErrorKind::InvalidStyleDeclaration));
+        assert!(vn.patch_flag.is_empty());
+    }
+
+    #[test]
+    fn test_trim_static_attrs() {
+        let vals = trimmed_attrs("<p class='  a   b  ' style=' color: red; ' title=' t '/>");
+        assert_eq!(vals, ["a b", "color: red;", " t "]);
+        // default option keeps values verbatim
+        let props = cast!(get_props("<p class=' a  b '/>"), Js::Props);
+        assert_eq!(cast!(&props[0].1, Js::StrLit).raw, " a  b ");
+    }
+}
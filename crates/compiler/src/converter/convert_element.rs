@@ -1,6 +1,7 @@
 use super::{
     build_props::{build_props, BuildProps},
-    v_slot, BaseConvertInfo, BaseConversion as BC, BaseIR, CoreConversion, Element, VStr,
+    v_slot, BaseConvertInfo, BaseConversion as BC, BaseIR, ComponentResolution, CoreConversion,
+    Element, VStr,
 };
 use crate::{
     converter::v_slot::check_wrong_slot,
@@ -19,6 +20,14 @@ pub fn convert_element<'a>(bc: &BC<'a>, mut e: Element<'a>) -> BaseIR<'a> {
         e.tag_type,
         ElementType::Plain | ElementType::Component
     ));
+    if e.tag_name == "template" && e.tag_type == ElementType::Plain {
+        // bare template is usually a typo. Still compile it as a fragment.
+        let error = CompilationError::new(ErrorKind::InvalidTemplatePlacement)
+            .with_location(e.location.clone());
+        bc.emit_error(error);
+        e.tag_type = ElementType::Template;
+        return convert_template(bc, e);
+    }
     let tag = resolve_element_tag(bc, &e);
     let is_block = should_use_block(&e, &tag);
     // curiously, we should first build children instead of props
@@ -44,6 +53,7 @@ pub fn convert_element<'a>(bc: &BC<'a>, mut e: Element<'a>) -> BaseIR<'a> {
         disable_tracking: false,
         is_component: e.is_component(),
         hoisted: Default::default(),
+        loc: Box::new(e.location.clone()),
     };
     IRNode::VNodeCall(vnode)
 }
@@ -77,6 +87,7 @@ pub fn convert_template<'a>(bc: &BC<'a>, mut e: Element<'a>) -> BaseIR<'a> {
         patch_flag,
         props,
         is_block: true, // only v-if/v-for(always block) or v-slot(as wrapper)
+        loc: Box::new(e.location),
         ..VNodeIR::default()
     })
 }
@@ -95,8 +106,8 @@ pub fn resolve_element_tag<'a>(bc: &BC<'a>, e: &Element<'a>) -> Js<'a> {
         Ok(call_expr) => return call_expr,
         Err(tag_name) => tag_name,
     };
-    // 1.5 v-is (deprecated)
-    if let Some(call_expr) = resolve_v_is_component(e, is_explicit_dynamic) {
+    // 1.5 v-is (removed, only with compat_v_is)
+    if let Some(call_expr) = resolve_v_is_component(bc, e, is_explicit_dynamic) {
         return call_expr;
     }
     // 2. built-in components (Teleport, Transition, KeepAlive, Suspense...)
@@ -112,6 +123,14 @@ pub fn resolve_element_tag<'a>(bc: &BC<'a>, e: &Element<'a>) -> Js<'a> {
     if let Some(from_setup) = resolve_setup_component(bc, tag) {
         return from_setup;
     }
+    // 3.5 user component with custom resolution
+    if let Some(resolver) = bc.option.component_resolver {
+        match resolver(tag) {
+            ComponentResolution::Runtime => (),
+            ComponentResolution::Identifier(s) => return Js::Src(s),
+            ComponentResolution::Expression(expr) => return bc.add_component_expr(expr),
+        }
+    }
     // 4. User component or Self referencing component (inferred from filename)
     let mut comp = VStr::raw(tag);
     if !bc.sfc_info.self_name.is_empty()
@@ -168,8 +187,12 @@ fn resolve_dynamic_component<'a>(
 }
 
 /// Returns dynamic component call if we found v-is, otherwise None
-fn resolve_v_is_component<'a>(e: &Element<'a>, is_explicit_dynamic: bool) -> Option<Js<'a>> {
-    if is_explicit_dynamic {
+fn resolve_v_is_component<'a>(
+    bc: &BC<'a>,
+    e: &Element<'a>,
+    is_explicit_dynamic: bool,
+) -> Option<Js<'a>> {
+    if is_explicit_dynamic || !bc.option.compat_v_is {
         return None;
     }
     let dir = find_dir(e, "is")?;
@@ -386,4 +409,59 @@ mod test {
         assert_eq!(tag.into_string(), "_component_comp");
         assert!(vn.is_component);
     }
+
+    #[test]
+    fn test_custom_element_helper() {
+        use crate::converter::{BaseConverter, Converter};
+        use crate::error::test::TestErrorHandler;
+        use crate::parser::{ParseOption, Parser};
+        use crate::scanner::test::base_scan;
+        use crate::util::get_vnode_call_helper;
+        use crate::SFCInfo;
+        use std::rc::Rc;
+        let parser = Parser::new(ParseOption {
+            is_native_element: |_| false,
+            is_custom_element: |s| s.starts_with("my-"),
+            ..Default::default()
+        });
+        let eh = Rc::new(TestErrorHandler);
+        let ast = parser.parse(base_scan("<my-el/><other-el/>"), eh.clone());
+        let bc = BaseConverter {
+            err_handle: eh,
+            option: Default::default(),
+        };
+        let info = SFCInfo::default();
+        let mut body = bc.convert_ir(ast, &info).body;
+        let other = cast!(body.pop().unwrap(), IRNode::VNodeCall);
+        assert!(other.is_component);
+        assert_eq!(get_vnode_call_helper(&other), RuntimeHelper::CREATE_VNODE);
+        let custom = cast!(body.pop().unwrap(), IRNode::VNodeCall);
+        assert!(!custom.is_component);
+        assert!(matches!(custom.tag, Js::StrLit(t) if t.raw == "my-el"));
+        assert_eq!(
+            get_vnode_call_helper(&custom),
+            RuntimeHelper::CREATE_ELEMENT_VNODE
+        );
+    }
+
+    #[test]
+    fn test_component_children_as_slot() {
+        let mut body = base_convert("<MyComp>hi</MyComp>").body;
+        let mut vn = cast!(body.remove(0), IRNode::VNodeCall);
+        assert_eq!(vn.children.len(), 1);
+        let slots = cast!(vn.children.remove(0), IRNode::VSlotUse);
+        assert!(slots.alterable_slots.is_empty());
+        assert_eq!(slots.stable_slots.len(), 1);
+        let default = &slots.stable_slots[0];
+        assert_eq!(cast!(&default.name, Js::StrLit).raw, "default");
+        assert!(matches!(default.body[0], IRNode::TextCall(_)));
+    }
+
+    #[test]
+    fn test_element_children_as_array() {
+        let mut body = base_convert("<div>hi</div>").body;
+        let vn = cast!(body.remove(0), IRNode::VNodeCall);
+        assert_eq!(vn.children.len(), 1);
+        assert!(matches!(vn.children[0], IRNode::TextCall(_)));
+    }
 }
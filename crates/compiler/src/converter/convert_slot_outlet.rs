@@ -17,7 +17,7 @@ pub fn convert_slot_outlet<'a>(bc: &BC<'a>, mut e: Element<'a>) -> BaseIR<'a> {
     let no_slotted = bc.no_slotted();
     let slot_props = slot_props.or({
         if !fallbacks.is_empty() || no_slotted {
-            Some(Js::Src("{}"))
+            Some(Js::Props(vec![]))
         } else {
             None
         }
@@ -93,3 +93,46 @@ fn process_slot_outlet<'a>(bc: &BC<'a>, e: &mut Element<'a>) -> NameAndProps<'a>
     }
     (slot_name, props)
 }
+
+#[cfg(test)]
+mod test {
+    use super::super::test::{assert_str_lit, base_convert};
+    use super::*;
+
+    #[test]
+    fn test_named_slot_outlet() {
+        let mut body = base_convert("<slot name='header' :foo='x'>fallback</slot>").body;
+        let slot = cast!(body.remove(0), IRNode::RenderSlotCall);
+        assert_str_lit(&slot.slot_name, "header");
+        let props = cast!(slot.slot_props.unwrap(), Js::Props);
+        assert_eq!(props.len(), 1);
+        assert_str_lit(&props[0].0, "foo");
+        assert_eq!(cast!(&props[0].1, Js::Simple).raw, "x");
+        assert_eq!(slot.fallbacks.len(), 1);
+        let text = cast!(&slot.fallbacks[0], IRNode::TextCall);
+        assert_str_lit(&text.texts[0], "fallback");
+    }
+
+    #[test]
+    fn test_dynamic_slot_name() {
+        let mut body = base_convert("<slot :name='n'/>").body;
+        let slot = cast!(body.remove(0), IRNode::RenderSlotCall);
+        assert_eq!(cast!(slot.slot_name, Js::Simple).raw, "n");
+        assert!(slot.slot_props.is_none());
+    }
+
+    #[test]
+    fn test_default_slot_outlet() {
+        let mut body = base_convert("<slot/>").body;
+        let slot = cast!(body.remove(0), IRNode::RenderSlotCall);
+        assert_str_lit(&slot.slot_name, "default");
+        assert!(slot.slot_props.is_none());
+        assert!(slot.fallbacks.is_empty());
+        // fallback needs an empty props placeholder
+        let mut body = base_convert("<slot><p/></slot>").body;
+        let slot = cast!(body.remove(0), IRNode::RenderSlotCall);
+        assert_str_lit(&slot.slot_name, "default");
+        assert!(cast!(slot.slot_props.unwrap(), Js::Props).is_empty());
+        assert_eq!(slot.fallbacks.len(), 1);
+    }
+}
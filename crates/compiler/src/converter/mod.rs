@@ -29,6 +29,7 @@ mod convert_slot_outlet;
 mod v_bind;
 mod v_for;
 mod v_if;
+mod v_is;
 pub mod v_model;
 pub mod v_on;
 mod v_slot;
@@ -37,16 +38,18 @@ use crate::{
     flags::{HelperCollector, RuntimeHelper},
     ir::{ConvertInfo, IRNode, IRRoot, JsExpr, TextIR, VNodeIR},
     parser::{SourceNode, TextNode},
-    util::{get_core_component, VStr},
+    util::{get_core_component, no, VStr},
     SFCInfo,
 };
 pub use v_bind::V_BIND;
+pub use v_is::V_IS;
 pub use v_model::V_MODEL;
 
 pub use crate::error::{CompilationError, ErrorHandler, RcErrHandle};
 pub use crate::parser::{AstNode, AstRoot, Directive, Element};
 use rustc_hash::{FxHashMap, FxHashSet};
 use smallvec::{smallvec, SmallVec};
+use std::cell::RefCell;
 use std::marker::PhantomData;
 use std::rc::Rc;
 
@@ -194,6 +197,7 @@ pub struct BaseConvertInfo<'a>(PhantomData<&'a ()>);
 #[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct ImportItem<'a> {
     pub exp: JsExpr<'a>,
+    /// module path, quoted in codegen
     pub path: &'a str,
 }
 
@@ -210,8 +214,16 @@ pub struct TopScope<'a> {
     pub hoists: Vec<Hoist<'a>>,
     /// assets need to be imported for template, e.g. image
     pub imports: Vec<ImportItem<'a>>,
-    /// counters for temporary variables created in template
-    pub temps: usize,
+    /// component expressions from component_resolver, declared before render
+    pub component_exprs: Vec<String>,
+    /// repeated expressions lifted to `_tempN` locals in render function
+    pub temps: Vec<JsExpr<'a>>,
+    /// max IR depth met in transformation, if reported
+    pub max_depth: Option<usize>,
+    /// number of `_cache` slots used by v-once/v-memo and cached handlers
+    pub cache_slots: usize,
+    /// number of nodes generated with a patch flag
+    pub patched_nodes: usize,
 }
 
 /// There are four different kinds of hoisting:
@@ -229,6 +241,9 @@ pub enum Hoist<'a> {
     /// 4. dynamic_props hint hoist:
     ///    <div :props="dynamic"> => const hoisted = ['props']
     DynamicPropsHint(FxHashSet<VStr<'a>>),
+    /// 5. identical hoist declared by another template in a batch:
+    ///    referenced by its variable name and not declared again
+    Shared(String),
 }
 
 impl<'a> ConvertInfo for BaseConvertInfo<'a> {
@@ -250,6 +265,22 @@ pub type DirConvertFn =
     for<'a> fn(&mut Directive<'a>, &Element<'a>, &dyn ErrorHandler) -> CoreDirConvRet<'a>;
 pub type DirectiveConverter = (&'static str, DirConvertFn);
 
+/// How a user component tag is referenced in render function.
+pub enum ComponentResolution<'a> {
+    /// Resolved at runtime by `resolveComponent`. This is the default.
+    Runtime,
+    /// A variable in scope referenced in render, e.g. an imported component.
+    Identifier(&'a str),
+    /// JS expression evaluated once as `_component_expr_N` before render,
+    /// e.g. `defineAsyncComponent(() => import('./Lazy.vue'))`.
+    Expression(String),
+}
+pub type ComponentResolver = for<'a> fn(&'a str) -> ComponentResolution<'a>;
+/// Called with tag, attribute name and value of static attributes.
+/// Returns the module path to import the url from, None keeps the url.
+/// The compiler names the import, e.g. `_imports_0` for `<img src="./a.png">`.
+pub type AssetUrlTransform = for<'a> fn(&str, &str, &'a str) -> Option<&'a str>;
+
 #[derive(Clone)]
 pub struct ConvertOption {
     /// For platform developers. Registers platform specific components written in JS.
@@ -258,6 +289,24 @@ pub struct ConvertOption {
     pub directive_converters: FxHashMap<&'static str, DirConvertFn>,
     pub is_dev: bool,
     pub need_reactivity: bool,
+    /// Trim static class/style values and collapse whitespace in class lists.
+    pub trim_static_attrs: bool,
+    /// Parse static style into an object like transformStyle in vuejs/core.
+    pub parse_static_style: bool,
+    /// Decides how user components not from setup bindings are referenced.
+    pub component_resolver: Option<ComponentResolver>,
+    /// Rewrites static asset urls like `src` into module imports.
+    pub transform_asset_url: Option<AssetUrlTransform>,
+    /// Resolve removed `v-is` as dynamic component instead of reporting error.
+    pub compat_v_is: bool,
+    /// Warn inline v-on statements that have more tokens than the limit.
+    pub max_handler_tokens: Option<usize>,
+    /// Lowercase static attribute names of elements like HTML parsers do.
+    pub lowercase_attrs: bool,
+    /// Elements whose attribute names keep original casing under lowercase_attrs.
+    pub is_custom_element: fn(&str) -> bool,
+    /// Custom elements whose v-model follows the component convention.
+    pub is_model_custom_element: fn(&str) -> bool,
 }
 
 impl Default for ConvertOption {
@@ -266,6 +315,15 @@ impl Default for ConvertOption {
             get_builtin_component: get_core_component,
             is_dev: true,
             need_reactivity: true,
+            trim_static_attrs: false,
+            parse_static_style: false,
+            component_resolver: None,
+            transform_asset_url: None,
+            compat_v_is: false,
+            max_handler_tokens: None,
+            lowercase_attrs: false,
+            is_custom_element: no,
+            is_model_custom_element: no,
             directive_converters: FxHashMap::default(),
         }
     }
@@ -291,8 +349,13 @@ impl Converter for BaseConverter {
             err_handle: self.err_handle.clone(),
             sfc_info: info,
             option: self.option.clone(),
+            imports: RefCell::default(),
+            component_exprs: RefCell::default(),
         };
-        conversion.convert_core_ir(ast)
+        let mut root = conversion.convert_core_ir(ast);
+        root.top_scope.imports = conversion.imports.into_inner();
+        root.top_scope.component_exprs = conversion.component_exprs.into_inner();
+        root
     }
 }
 
@@ -300,6 +363,10 @@ pub struct BaseConversion<'a> {
     pub err_handle: RcErrHandle,
     pub sfc_info: &'a SFCInfo<'a>,
     pub option: Rc<ConvertOption>,
+    /// module imports of asset urls
+    pub imports: RefCell<Vec<ImportItem<'a>>>,
+    /// component expressions from component_resolver
+    pub component_exprs: RefCell<Vec<String>>,
 }
 pub type BaseRoot<'a> = IRRoot<BaseConvertInfo<'a>>;
 pub type BaseIR<'a> = IRNode<BaseConvertInfo<'a>>;
@@ -322,6 +389,12 @@ impl<'a> CoreConversion<'a, BaseConvertInfo<'a>> for BaseConversion<'a> {
         dir: &mut Directive<'a>,
         e: &mut Element<'a>,
     ) -> CoreDirConvRet<'a> {
+        if let (Some(max), "on") = (self.option.max_handler_tokens, dir.name) {
+            v_on::warn_complex_handler(dir, max, self.err_handle.as_ref());
+        }
+        if dir.name == "model" && (self.option.is_model_custom_element)(e.tag_name) {
+            return v_model::convert_v_model_event(dir, e, self.err_handle.as_ref());
+        }
         if let Some(convert) = self.option.directive_converters.get(dir.name) {
             convert(dir, e, self.err_handle.as_ref())
         } else {
@@ -357,20 +430,29 @@ impl<'a> CoreConversion<'a, BaseConvertInfo<'a>> for BaseConversion<'a> {
     }
     fn convert_text(&self, text: TextNode<'a>) -> BaseIR<'a> {
         // TODO: reduce allocation by push to existing
+        let locs = smallvec![text.location; text.text.len()];
         let texts = text.text.into_iter().map(JsExpr::StrLit).collect();
         IRNode::TextCall(TextIR {
             fast_path: false,
             need_patch: false,
             texts,
+            locs,
         })
     }
     fn convert_interpolation(&self, interp: SourceNode<'a>) -> BaseIR<'a> {
-        let expr = JsExpr::simple(interp.source);
-        let call = JsExpr::Call(RuntimeHelper::TO_DISPLAY_STRING, vec![expr]);
+        // string literal needs no toDisplayString and can merge with static text
+        let text = match as_string_literal(interp.source) {
+            Some(lit) => JsExpr::str_lit(lit),
+            None => {
+                let expr = JsExpr::simple(interp.source);
+                JsExpr::Call(RuntimeHelper::TO_DISPLAY_STRING, vec![expr])
+            }
+        };
         IRNode::TextCall(TextIR {
             fast_path: false,
             need_patch: false,
-            texts: smallvec![call],
+            texts: smallvec![text],
+            locs: smallvec![interp.location],
         })
     }
     fn convert_template(&self, e: Element<'a>) -> BaseIR<'a> {
@@ -385,6 +467,41 @@ impl<'a> BaseConversion<'a> {
     fn no_slotted(&self) -> bool {
         self.sfc_info.scope_id.is_some() && !self.sfc_info.slotted
     }
+    /// Returns the local name of the module, imported once per path.
+    fn add_import(&self, path: &'a str) -> JsExpr<'a> {
+        let mut imports = self.imports.borrow_mut();
+        if let Some(i) = imports.iter().find(|i| i.path == path) {
+            return i.exp.clone();
+        }
+        let exp = JsExpr::Compound(vec![JsExpr::Src("_imports_"), JsExpr::Num(imports.len())]);
+        imports.push(ImportItem {
+            exp: exp.clone(),
+            path,
+        });
+        exp
+    }
+    /// Returns the local name of the component expression, declared once.
+    fn add_component_expr(&self, expr: String) -> JsExpr<'a> {
+        let mut exprs = self.component_exprs.borrow_mut();
+        let i = match exprs.iter().position(|e| *e == expr) {
+            Some(i) => i,
+            None => {
+                exprs.push(expr);
+                exprs.len() - 1
+            }
+        };
+        JsExpr::Compound(vec![JsExpr::Src("_component_expr_"), JsExpr::Num(i)])
+    }
+}
+
+/// Returns content of a quoted literal like 'x' or "x".
+/// Literals with escapes are left to runtime to avoid decoding JS here.
+fn as_string_literal(src: &str) -> Option<&str> {
+    let src = src.trim();
+    let quote = src.chars().next().filter(|c| *c == '\'' || *c == '"')?;
+    let content = src.get(1..src.len() - 1)?;
+    let is_single = src.ends_with(quote) && !content.contains([quote, '\\']);
+    is_single.then_some(content)
 }
 
 #[cfg(test)]
@@ -416,6 +533,18 @@ pub mod test {
         let ast = base_parse(s);
         bc.convert_ir(ast, &SFC_INFO)
     }
+    #[test]
+    fn test_string_literal_interpolation() {
+        assert_eq!(as_string_literal(" 'x' "), Some("x"));
+        assert_eq!(as_string_literal(r#""y""#), Some("y"));
+        assert_eq!(as_string_literal("'a' + 'b'"), None);
+        assert_eq!(as_string_literal(r"'\n'"), None);
+        assert_eq!(as_string_literal("'"), None);
+        assert_eq!(as_string_literal("x"), None);
+        let body = base_convert("{{ 'x' }}").body;
+        let text = cast!(&body[0], IRNode::TextCall);
+        assert_eq!(cast!(&text.texts[0], Js::StrLit).raw, "x");
+    }
     pub fn handler_convert(s: &str) -> BaseRoot {
         let convs = vec![
             v_bind::V_BIND,
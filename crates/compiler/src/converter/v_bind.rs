@@ -6,19 +6,25 @@ use super::{
     JsExpr as Js,
 };
 use crate::parser::DirectiveArg;
+use crate::util::VStr;
 
 // this module process v-bind without arg and with arg.
 pub fn convert_v_bind<'a>(
     dir: &mut Directive<'a>,
-    _: &Element<'a>,
+    e: &Element<'a>,
     eh: &dyn ErrorHandler,
 ) -> CoreDirConvRet<'a> {
-    let expr = if let Some(error) = dir.check_empty_expr(ErrorKind::VBindNoExpression) {
+    let expr = if let Some(name) = same_name_shorthand(dir) {
+        // <p :test> is short for <p :test="test">
+        let mut name = VStr::raw(name);
+        name.camelize();
+        Js::simple(name)
+    } else if let Some(error) = dir.check_empty_expr(ErrorKind::VBindNoExpression) {
         eh.on_error(error);
         if dir.argument.is_none() {
             return DirectiveConvertResult::Dropped;
         } else {
-            // <p :test> returns {test: ""}
+            // <p :test=""> returns {test: ""}
             Js::str_lit("")
         }
     } else {
@@ -41,7 +47,16 @@ pub fn convert_v_bind<'a>(
                 Js::Compound(vec![Js::Src("("), e, Js::Src(") || ''")])
             }
         };
-        // TODO: handle .attr, .prop, modifiers in DOM
+        // component props are declared in camelCase, e.g. :my-prop => myProp.
+        // native and custom elements keep attribute names as authored.
+        if e.is_component() {
+            if let Js::StrLit(ref mut s) = arg {
+                if !is_verbatim_attr(s.raw) {
+                    s.camelize();
+                }
+            }
+        }
+        // TODO: handle .attr modifier in DOM
         if modifiers.contains(&"camel") {
             arg = match arg {
                 Js::StrLit(ref mut s) => {
@@ -51,6 +66,16 @@ pub fn convert_v_bind<'a>(
                 a => Js::Call(RuntimeHelper::CAMELIZE, vec![a]),
             }
         }
+        // runtime sets keys prefixed by . as DOM property
+        if modifiers.contains(&"prop") {
+            arg = match arg {
+                Js::StrLit(ref mut s) => {
+                    s.prefix_prop();
+                    arg
+                }
+                a => Js::Compound(vec![Js::Src("'.' + "), a]),
+            }
+        }
         Js::Props(vec![(arg, expr)])
     } else {
         expr
@@ -61,7 +86,71 @@ pub fn convert_v_bind<'a>(
     }
 }
 
+/// Returns the static argument of a value-less v-bind.
+/// Dynamic argument cannot be used as identifier.
+fn same_name_shorthand<'a>(dir: &Directive<'a>) -> Option<&'a str> {
+    if dir.expression.is_some() {
+        return None;
+    }
+    match dir.argument {
+        Some(DirectiveArg::Static(name)) => Some(name),
+        _ => None,
+    }
+}
+
 pub const V_BIND: DirectiveConverter = ("bind", convert_v_bind);
 
+/// data-*, aria-* and namespace declarations are attributes, never props.
+fn is_verbatim_attr(name: &str) -> bool {
+    name.starts_with("data-")
+        || name.starts_with("aria-")
+        || name == "xmlns"
+        || name.starts_with("xmlns:")
+}
+
 #[cfg(test)]
-mod test {}
+mod test {
+    use super::super::test::base_convert;
+    use super::*;
+    use crate::{flags::PatchFlag, ir::IRNode};
+
+    #[test]
+    fn test_prop_modifier() {
+        for case in ["<input :value.prop='v'/>", "<input .value='v'/>"] {
+            let mut body = base_convert(case).body;
+            let vn = cast!(body.remove(0), IRNode::VNodeCall);
+            let props = cast!(vn.props.unwrap(), Js::Props);
+            let key = cast!(&props[0].0, Js::StrLit);
+            assert_eq!(key.into_string(), ".value", "{}", case);
+            let dynamic: Vec<_> = vn.dynamic_props.iter().map(|v| v.into_string()).collect();
+            assert_eq!(dynamic, [".value"]);
+            assert!(vn.patch_flag == PatchFlag::PROPS);
+        }
+    }
+
+    #[test]
+    fn test_prop_modifier_not_class_binding() {
+        let mut body = base_convert("<p :class.prop='c'/>").body;
+        let vn = cast!(body.remove(0), IRNode::VNodeCall);
+        assert!(vn.patch_flag == PatchFlag::PROPS);
+        let mut body = base_convert("<p :[k].prop='v'/>").body;
+        let vn = cast!(body.remove(0), IRNode::VNodeCall);
+        let props = cast!(vn.props.unwrap(), Js::Props);
+        let key = cast!(&props[0].0, Js::Compound);
+        assert_eq!(cast!(key[0], Js::Src), "'.' + ");
+        assert!(vn.patch_flag == PatchFlag::FULL_PROPS);
+    }
+
+    #[test]
+    fn test_camelize_component_prop() {
+        let key_of = |case| {
+            let mut body = base_convert(case).body;
+            let vn = cast!(body.remove(0), IRNode::VNodeCall);
+            let props = cast!(vn.props.unwrap(), Js::Props);
+            cast!(&props[0].0, Js::StrLit).into_string()
+        };
+        assert_eq!(key_of("<comp :my-prop='a'/>"), "myProp");
+        assert_eq!(key_of("<comp :data-id='a'/>"), "data-id");
+        assert_eq!(key_of("<div :my-prop='a'/>"), "my-prop");
+    }
+}
@@ -7,7 +7,7 @@ use crate::{
     flags::PatchFlag,
     ir::{ForNodeIR, ForParseResult, IRNode, JsExpr as Js},
     parser::ElementType,
-    util::{find_dir_empty, find_prop, VStr},
+    util::{find_dir_empty, find_prop, is_simple_identifier, rslint, VStr},
 };
 use smallvec::SmallVec;
 
@@ -32,8 +32,8 @@ pub fn convert_for<'a>(bc: &BaseConversion, d: Directive<'a>, n: BaseIR<'a>) ->
     }
     let expr = d.expression.expect("v-for must have expression");
     let (source, parse_result) = match parse_for_expr(expr.content) {
-        Some(parsed) => parsed,
-        None => {
+        Some(parsed) if is_valid_alias(&parsed.1) => parsed,
+        _ => {
             let error = CompilationError::new(ErrorKind::VForMalformedExpression)
                 .with_location(expr.location.clone());
             bc.emit_error(error);
@@ -53,17 +53,13 @@ pub fn convert_for<'a>(bc: &BaseConversion, d: Directive<'a>, n: BaseIR<'a>) ->
 
 type ParsedFor<'a> = (Js<'a>, ForParseResult<BaseConvertInfo<'a>>);
 
-const PARENS: &[char] = &['(', ')'];
 fn parse_for_expr(expr: VStr) -> Option<ParsedFor> {
     // split source and binding
-    let (lhs, rhs) = expr
-        .raw
-        .split_once(" in ")
-        .or_else(|| expr.raw.split_once(" of "))
-        .map(|(l, r)| (l.trim_matches(PARENS), r.trim()))?;
-    if rhs.is_empty() {
+    let (lhs, rhs) = split_in_of(expr.raw).map(|(l, r)| (l.trim(), r.trim()))?;
+    if rhs.is_empty() || rslint::parse_js_expr(rhs).is_none() {
         return None;
     }
+    let lhs = strip_alias_parens(lhs)?;
     // split iterator by ,
     let (val, key, idx) = split_v_for_iter(lhs);
     Some((
@@ -76,6 +72,29 @@ fn parse_for_expr(expr: VStr) -> Option<ParsedFor> {
     ))
 }
 
+// (value, key, index) must have both parens. Strip only the outermost pair
+// so default values like `(a = f())` keep their own parens.
+fn strip_alias_parens(lhs: &str) -> Option<&str> {
+    match (lhs.strip_prefix('('), lhs.ends_with(')')) {
+        (Some(inner), true) => Some(&inner[..inner.len() - 1]),
+        (None, false) => Some(lhs),
+        _ => None,
+    }
+}
+
+// equivalent to this JS regexp
+// /([\s\S]*?)\s+(?:in|of)\s+([\s\S]*)/
+fn split_in_of(raw: &str) -> Option<(&str, &str)> {
+    (1..raw.len()).find_map(|i| {
+        let (pre, post) = (raw.get(..i)?, raw.get(i..)?);
+        let rest = post
+            .strip_prefix("in")
+            .or_else(|| post.strip_prefix("of"))?;
+        let is_sep = pre.ends_with(char::is_whitespace) && rest.starts_with(char::is_whitespace);
+        is_sep.then_some((pre, rest))
+    })
+}
+
 const DESTRUCTING: &[char] = &['}', ']'];
 fn split_v_for_iter(mut lhs: &str) -> (&str, Option<&str>, Option<&str>) {
     let mut split = SmallVec::<[&str; 3]>::new();
@@ -95,6 +114,24 @@ fn split_v_for_iter(mut lhs: &str) -> (&str, Option<&str>, Option<&str>) {
     }
 }
 
+/// Aliases are arrow function params: an identifier or a destructuring
+/// pattern, optionally with default value. Omitted alias is allowed.
+fn is_valid_alias(parsed: &ForParseResult<BaseConvertInfo>) -> bool {
+    let is_param = |e: &Js| {
+        let s = cast!(e, Js::Param);
+        if s.is_empty() || is_simple_identifier(VStr::raw(s)) {
+            return true;
+        }
+        // destructuring or default value needs the full parser
+        matches!(rslint::parse_fn_param(s), Some(p) if p.parameters().count() == 1)
+    };
+    let mut aliases = Some(&parsed.value)
+        .into_iter()
+        .chain(&parsed.key)
+        .chain(&parsed.index);
+    aliases.all(is_param)
+}
+
 fn convert_memo_in_v_for<'a, F>(n: BaseIR<'a>, get_key: F) -> BaseIR<'a>
 where
     F: Fn() -> Option<Js<'a>>,
@@ -142,7 +179,6 @@ fn check_template_v_for_key<'a, T: ConvertInfo, C: CoreConversion<'a, T> + ?Size
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::cast;
     fn to_str(e: Js) -> &str {
         cast!(e, Js::Param)
     }
@@ -174,9 +210,86 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_object_three_aliases() {
+        let src = "(value, name, index) in object";
+        check_equal(src, ("object", "value", "name".into(), "index".into()));
+        check_equal(
+            "(value, name, index = f()) of object",
+            ("object", "value", "name".into(), "index = f()".into()),
+        );
+        let (_, ret) = parse_for_expr(VStr::raw(src)).unwrap();
+        assert!(is_valid_alias(&ret));
+    }
+
+    #[test]
+    fn test_numeric_source() {
+        check_equal("n in 10", ("10", "n", None, None));
+        let (_, ret) = parse_for_expr(VStr::raw("(n, i) in 10")).unwrap();
+        assert!(is_valid_alias(&ret));
+    }
+
+    #[test]
+    fn test_destructured_alias() {
+        check_equal("{ a, b } in list", ("list", "{ a, b }", None, None));
+        check_equal("([a, b], i) of list", ("list", "[a, b]", "i".into(), None));
+        for src in ["{ a, b } in list", "([a, b], i) of list", "(, i) in list"] {
+            let (_, ret) = parse_for_expr(VStr::raw(src)).unwrap();
+            assert!(is_valid_alias(&ret), "{}", src);
+        }
+    }
+
+    #[test]
+    fn test_malformed_alias() {
+        for src in [
+            "a b in list",
+            "(a, b c) in list",
+            "1 in list",
+            "(a, {b}) in list",
+            "{ a, } b in list",
+            "[a + 1] in list",
+            "(a, b, c, d) in list",
+        ] {
+            let (_, ret) = parse_for_expr(VStr::raw(src)).unwrap();
+            assert!(!is_valid_alias(&ret), "{}", src);
+        }
+    }
+
+    #[test]
+    fn test_alias_default_value() {
+        for src in [
+            "(a = 1, i) in list",
+            "({ a = 1 }, i) in list",
+            "[a, ...b] in list",
+        ] {
+            let (_, ret) = parse_for_expr(VStr::raw(src)).unwrap();
+            assert!(is_valid_alias(&ret), "{}", src);
+        }
+    }
+
+    #[test]
+    fn test_whitespace_separator() {
+        check_equal("a\tin\nlist", ("list", "a", None, None));
+        check_equal(
+            "(item, index) of  list",
+            ("list", "item", "index".into(), None),
+        );
+        check_equal("index in indices", ("indices", "index", None, None));
+    }
+
     #[test]
     fn test_parse_invalid_for() {
-        for src in &["", "           in             "] {
+        for src in &[
+            "",
+            "           in             ",
+            "item list",
+            "item in list list",
+            "item in (",
+            "item inlist",
+            "(a, b in list",
+            "(a, b, c in object",
+            "a, b) in list",
+        ] {
             assert!(parse_for_expr(VStr::raw(src)).is_none());
         }
     }
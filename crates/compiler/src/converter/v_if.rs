@@ -10,7 +10,7 @@ use crate::{
     util::{find_dir_empty, find_prop, VStr},
 };
 use rustc_hash::FxHashSet;
-use std::{iter::Peekable, vec::IntoIter};
+use std::{collections::VecDeque, iter::Peekable, vec::IntoIter};
 
 // TODO: reduce vec allocation by using Drain iter
 // but using drain need GAT
@@ -22,6 +22,8 @@ pub enum PreGroup<'a> {
 struct PreGroupIter<'a> {
     inner: Peekable<IntoIter<AstNode<'a>>>,
     group: Vec<Element<'a>>,
+    /// comments met after a v-if group, dropped if more branches follow
+    comments: VecDeque<AstNode<'a>>,
 }
 
 impl<'a> PreGroupIter<'a> {
@@ -30,6 +32,7 @@ impl<'a> PreGroupIter<'a> {
         Self {
             inner: children.into_iter().peekable(),
             group: Vec::with_capacity(len),
+            comments: VecDeque::new(),
         }
     }
     fn flush_group(&mut self) -> Option<PreGroup<'a>> {
@@ -55,6 +58,12 @@ impl<'a> PreGroupIter<'a> {
 impl<'a> Iterator for PreGroupIter<'a> {
     type Item = PreGroup<'a>;
     fn next(&mut self) -> Option<Self::Item> {
+        // comments following a finished group are kept as is
+        if self.group.is_empty() {
+            if let Some(c) = self.comments.pop_front() {
+                return Some(PreGroup::StandAlone(c));
+            }
+        }
         while let Some(n) = self.inner.peek() {
             // group elements if they have v-if/v-else
             let found = n
@@ -65,6 +74,8 @@ impl<'a> Iterator for PreGroupIter<'a> {
                 if d.get_ref().name == "if" && !self.group.is_empty() {
                     return self.flush_group();
                 }
+                // comments between branches are removed. #3619
+                self.comments.clear();
                 let n = self.inner.next().unwrap(); // must next to advance
                 self.group.push(n.into_element());
             } else if let AstNode::Text(s) = n {
@@ -76,22 +87,29 @@ impl<'a> Iterator for PreGroupIter<'a> {
                 // skip whitespace when v-if precedes
                 self.inner.next().unwrap();
             } else if matches!(n, &AstNode::Comment(_)) {
-                // ignore comments for now. #3619
-                return self.next_standalone();
+                if self.group.is_empty() {
+                    return self.next_standalone();
+                }
+                // hold comments until we know if more branches follow
+                let c = self.inner.next().unwrap();
+                self.comments.push_back(c);
             } else {
                 break;
             }
         }
         // vec emptied or next element has no v-if
         // first, flush preceding group
-        self.flush_group().or_else(|| {
-            // if no group, consume next standalone element if available
-            self.next_standalone()
-        })
+        self.flush_group()
+            .or_else(|| self.comments.pop_front().map(PreGroup::StandAlone))
+            .or_else(|| {
+                // if no group, consume next standalone element if available
+                self.next_standalone()
+            })
     }
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        (0, self.inner.size_hint().1)
+        let pending = self.comments.len();
+        (0, self.inner.size_hint().1.map(|n| n + pending))
     }
 }
 
@@ -224,7 +242,7 @@ fn report_duplicate_v_if<'a>(c: &BC<'a>, e: &mut Element<'a>) {
 mod test {
     use super::super::test::*;
     use super::*;
-    use crate::cast;
+    use crate::flags::RuntimeHelper;
 
     fn test_no_panic() {
         let cases = [
@@ -251,4 +269,48 @@ mod test {
         let cond = cast!(condition, Js::Simple);
         assert_eq!(cond.into_string(), "true");
     }
+
+    #[test]
+    fn test_multi_child_template_branch() {
+        let case = "<template v-if='c'><a/><b/></template><template v-else><a/></template>";
+        let body = base_convert(case).body;
+        let v_if = cast!(&body[0], IRNode::If);
+        assert_eq!(v_if.branches.len(), 2);
+        for (branch, count) in v_if.branches.iter().zip([2, 1]) {
+            let vn = cast!(&*branch.child, IRNode::VNodeCall);
+            assert!(matches!(vn.tag, Js::Symbol(RuntimeHelper::FRAGMENT)));
+            assert!(vn.is_block);
+            assert_eq!(vn.children.len(), count);
+        }
+    }
+
+    #[test]
+    fn test_if_else_if_else_group() {
+        let case = "<p v-if='a'/><p v-else-if='b'/><p v-else/>";
+        let body = base_convert(case).body;
+        assert_eq!(body.len(), 1);
+        let v_if = cast!(&body[0], IRNode::If);
+        assert_eq!(v_if.branches.len(), 3);
+        let conds: Vec<_> = v_if
+            .branches
+            .iter()
+            .map(|b| b.condition.as_ref().map(|c| cast!(c, Js::Simple).raw))
+            .collect();
+        assert_eq!(conds, [Some("a"), Some("b"), None]);
+    }
+
+    #[test]
+    fn test_comment_between_branches() {
+        let case = "<p v-if='a'/>\n<!--c-->\n<p v-else/>";
+        let body = base_convert(case).body;
+        assert_eq!(body.len(), 1);
+        let v_if = cast!(&body[0], IRNode::If);
+        assert_eq!(v_if.branches.len(), 2);
+        // comment not followed by a branch is kept in place
+        let body = base_convert("<p v-if='a'/><!--c--><p/>").body;
+        assert_eq!(body.len(), 3);
+        assert!(matches!(body[0], IRNode::If(_)));
+        assert!(matches!(body[1], IRNode::CommentCall("c")));
+        assert!(matches!(body[2], IRNode::VNodeCall(_)));
+    }
 }
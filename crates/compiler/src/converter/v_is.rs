@@ -0,0 +1,20 @@
+use crate::error::{CompilationError as Error, CompilationErrorKind as ErrorKind};
+
+use super::{
+    CoreDirConvRet, Directive, DirectiveConvertResult, DirectiveConverter, Element, ErrorHandler,
+};
+
+// v-is was removed in Vue 3.1. <tr v-is="row"> should be migrated to
+// <tr is="vue:row"> or <component :is="row">. It reaches here only when
+// compat_v_is is off, otherwise it is resolved as dynamic component.
+pub fn convert_v_is<'a>(
+    dir: &mut Directive<'a>,
+    _: &Element<'a>,
+    eh: &dyn ErrorHandler,
+) -> CoreDirConvRet<'a> {
+    let error = Error::new(ErrorKind::VIsRemoved).with_location(dir.location.clone());
+    eh.on_error(error);
+    DirectiveConvertResult::Dropped
+}
+
+pub const V_IS: DirectiveConverter = ("is", convert_v_is);
@@ -2,7 +2,7 @@ use super::{
     CoreDirConvRet, Directive, DirectiveConvertResult, DirectiveConverter, Element, ErrorHandler,
 };
 use crate::{
-    error::CompilationErrorKind as ErrorKind,
+    error::{CompilationError, CompilationErrorKind as ErrorKind},
     flags::RuntimeHelper,
     ir::{HandlerType, JsExpr as Js},
     parser::DirectiveArg,
@@ -123,11 +123,35 @@ pub fn get_handler_type(val: VStr) -> HandlerType {
     }
 }
 
+// warn inline statements that are too long, e.g.
+// @click="a = b; if (c) { d() } else { e = f.g(h) }"
+// they are hard to read and better extracted to a method.
+pub fn warn_complex_handler(dir: &Directive, max_tokens: usize, eh: &dyn ErrorHandler) {
+    let expr = match &dir.expression {
+        Some(expr) => expr,
+        None => return,
+    };
+    if !matches!(get_handler_type(expr.content), HandlerType::InlineStmt) {
+        return;
+    }
+    // invalid expression is reported elsewhere
+    if matches!(rslint::count_stmt_tokens(expr.content.raw), Some(n) if n > max_tokens) {
+        let error = CompilationError::new(ErrorKind::ComplexInlineHandler)
+            .with_location(expr.location.clone());
+        eh.on_error(error);
+    }
+}
+
 pub const V_ON: DirectiveConverter = ("on", convert_v_on);
 
 #[cfg(test)]
 mod test {
+    use super::super::{BaseConverter, ConvertOption, Converter};
     use super::*;
+    use crate::error::VecErrorHandler;
+    use crate::parser::test::base_parse;
+    use crate::SFCInfo;
+    use std::rc::Rc;
     #[test]
     fn test_is_fn_expr() {
         let positive_cases = [
@@ -150,4 +174,57 @@ mod test {
             assert!(!is_fn_exp(case), "{}", case);
         }
     }
+
+    fn warnings(s: &str, max_handler_tokens: Option<usize>) -> Vec<CompilationError> {
+        let eh = Rc::new(VecErrorHandler::default());
+        let option = ConvertOption {
+            directive_converters: vec![V_ON].into_iter().collect(),
+            max_handler_tokens,
+            ..Default::default()
+        };
+        let bc = BaseConverter {
+            err_handle: eh.clone(),
+            option: Rc::new(option),
+        };
+        bc.convert_ir(base_parse(s), &SFCInfo::default());
+        let mut errors = eh.error_mut();
+        errors
+            .drain(..)
+            .filter(|e| matches!(e.kind, ErrorKind::ComplexInlineHandler))
+            .collect()
+    }
+    fn warning_count(s: &str, max_handler_tokens: Option<usize>) -> usize {
+        warnings(s, max_handler_tokens).len()
+    }
+
+    #[test]
+    fn test_complex_inline_handler() {
+        let case = "<p @click='a = b; if (c) { d(e, f) } else { g = h.i(j) }'/>";
+        assert_eq!(warning_count(case, Some(10)), 1);
+        assert_eq!(warning_count(case, Some(100)), 0);
+        assert_eq!(warning_count(case, None), 0);
+        assert_eq!(warning_count("<p @click='a++'/>", Some(10)), 0);
+    }
+
+    #[test]
+    fn test_complex_handler_location() {
+        let case = "<p id='a' @click='a = b; c(d, e)'/>";
+        let errors = warnings(case, Some(5));
+        assert_eq!(errors.len(), 1);
+        let loc = &errors[0].location;
+        assert_eq!(&case[loc.start.offset..loc.end.offset], "'a = b; c(d, e)'");
+    }
+
+    #[test]
+    fn test_non_inline_handler() {
+        let case = "<p @click='(e) => { a = b; c(d, e, f); g = h.i(j) }'/>";
+        assert_eq!(warning_count(case, Some(5)), 0);
+        assert_eq!(warning_count("<p @click='a.b.c.d.e.f.g'/>", Some(5)), 0);
+    }
+
+    #[test]
+    fn test_count_tokens() {
+        assert_eq!(rslint::count_stmt_tokens("a++"), Some(2));
+        assert_eq!(rslint::count_stmt_tokens("a = b; c()"), Some(7));
+    }
 }
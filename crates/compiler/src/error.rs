@@ -1,12 +1,20 @@
 use super::SourceLocation;
-use std::cell::{Ref, RefMut, RefCell};
-use std::fmt;
-use std::rc::Rc;
+use alloc::{boxed::Box, rc::Rc, vec, vec::Vec};
+use core::cell::{Ref, RefMut, RefCell};
+use core::fmt;
 
-pub trait ErrorKind {
+pub mod diagnostic;
+
+/// Debug output of the kind is used as error code in diagnostics.
+pub trait ErrorKind: fmt::Debug {
     fn msg(&self) -> &'static str;
+    /// Warnings do not break compilation output.
+    fn is_warning(&self) -> bool {
+        false
+    }
 }
 
+#[derive(Debug)]
 pub enum CompilationErrorKind {
     AbruptClosingOfEmptyComment,
     CDataInHtmlContent,
@@ -42,6 +50,7 @@ pub enum CompilationErrorKind {
     MissingDirectiveArg,
     MissingDirectiveMod,
     InvalidVSlotModifier,
+    UnexpectedInterpolationInAttribute,
 
     // transform errors
     VIfNoExpression,
@@ -63,10 +72,21 @@ pub enum CompilationErrorKind {
     VModelNoExpression,
     VModelMalformedExpression,
     VModelOnScopeVariable,
+    VIsRemoved,
     InvalidExpression,
 
     UnexpectedDirExpression,
     KeepAliveInvalidChildren,
+    NestingTooDeep,
+    ForbiddenHelper,
+
+    // recoverable warnings
+    ConstantInterpolation,
+    ComplexInlineHandler,
+    TooManyDynamicProps,
+    UnexpectedDoctype,
+    InvalidTemplatePlacement,
+    InvalidStyleDeclaration,
 
     // generic errors
     PrefixIdNotSupported,
@@ -89,6 +109,8 @@ pub struct CompilationError {
     pub kind: CompilationErrorKind,
     pub additional_message: Option<&'static str>,
     pub location: SourceLocation,
+    /// template file name, shown as message prefix if present
+    pub filename: Option<Rc<str>>,
 }
 
 impl CompilationError {
@@ -97,6 +119,7 @@ impl CompilationError {
             kind,
             additional_message: None,
             location: Default::default(),
+            filename: None,
         }
     }
     #[must_use]
@@ -110,11 +133,17 @@ impl CompilationError {
         self
     }
     #[must_use]
+    pub fn with_filename(mut self, filename: Rc<str>) -> Self {
+        self.filename = Some(filename);
+        self
+    }
+    #[must_use]
     pub fn extended<K: ErrorKind + 'static>(kind: K) -> Self {
         Self {
             kind: CompilationErrorKind::ExtendPoint(Box::new(kind)),
             additional_message: None,
             location: Default::default(),
+            filename: None,
         }
     }
 
@@ -166,6 +195,8 @@ fn msg(kind: &CompilationErrorKind) -> &'static str {
         MissingDirectiveArg => "Directive argument was expected.",
         MissingDirectiveMod => "Directive modifier was expected.",
         InvalidVSlotModifier => "v-slot does not take modifier.",
+        UnexpectedInterpolationInAttribute =>
+            "Interpolation inside attributes has been removed. Use v-bind or the colon shorthand instead. For example, instead of <div id=\"{{ val }}\">, use <div :id=\"val\">.",
 
         // transform errors
         VIfNoExpression => "v-if/v-else-if is missing expression.",
@@ -190,9 +221,25 @@ fn msg(kind: &CompilationErrorKind) -> &'static str {
         VModelMalformedExpression => "v-model value must be a valid JavaScript member expression.",
         VModelOnScopeVariable =>
             "v-model cannot be used on v-for or v-slot scope variables because they are not writable.",
+        VIsRemoved =>
+            r#"v-is has been removed. Use :is on <component> or is="vue:xxx" on native elements."#,
         InvalidExpression => "Error parsing JavaScript expression: ",
         UnexpectedDirExpression => "This directive does not accept any epxression.",
         KeepAliveInvalidChildren => "<KeepAlive> expects exactly one child component.",
+        NestingTooDeep => "Template nesting is too deep. Deeper elements are ignored.",
+        ForbiddenHelper => "Template uses a runtime helper in the deny list: ",
+
+        // recoverable warnings
+        ConstantInterpolation =>
+            "Interpolation is a constant expression. Consider writing it as static text.",
+        ComplexInlineHandler =>
+            "Inline handler is too complex. Consider extracting it to a method.",
+        TooManyDynamicProps =>
+            "Element has too many dynamic props. Consider binding an object with v-bind.",
+        UnexpectedDoctype => "Doctype or XML declaration is not allowed in template and is ignored.",
+        InvalidTemplatePlacement =>
+            "<template> without v-if, v-for or v-slot has no effect. Its children are rendered as a fragment.",
+        InvalidStyleDeclaration => "Malformed declaration in static style is ignored.",
 
         // generic errors
         PrefixIdNotSupported =>
@@ -209,10 +256,26 @@ impl ErrorKind for CompilationErrorKind {
     fn msg(&self) -> &'static str {
         msg(self)
     }
+    fn is_warning(&self) -> bool {
+        use CompilationErrorKind::*;
+        match self {
+            ConstantInterpolation
+            | ComplexInlineHandler
+            | TooManyDynamicProps
+            | UnexpectedDoctype
+            | InvalidTemplatePlacement
+            | InvalidStyleDeclaration => true,
+            ExtendPoint(err) => err.is_warning(),
+            _ => false,
+        }
+    }
 }
 
 impl fmt::Display for CompilationError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(filename) = &self.filename {
+            write!(f, "{}: ", filename)?;
+        }
         if let Some(additional) = &self.additional_message {
             write!(f, "{}{}", self.msg(), additional)
         } else {
@@ -267,6 +330,25 @@ impl ErrorHandler for VecErrorHandler {
     }
 }
 
+/// Attaches file name to errors before forwarding them to inner handler.
+pub struct FilenameErrorHandler {
+    filename: Rc<str>,
+    inner: RcErrHandle,
+}
+impl FilenameErrorHandler {
+    pub fn new(filename: &str, inner: RcErrHandle) -> Self {
+        Self {
+            filename: filename.into(),
+            inner,
+        }
+    }
+}
+impl ErrorHandler for FilenameErrorHandler {
+    fn on_error(&self, e: CompilationError) {
+        self.inner.on_error(e.with_filename(self.filename.clone()));
+    }
+}
+
 #[cfg(test)]
 pub mod test {
     use super::ErrorHandler;
@@ -0,0 +1,139 @@
+//! Converts CompilationError to editor diagnostics, e.g. for LSP servers.
+//! Positions are 0-based and columns count UTF-16 code units as LSP requires.
+
+use super::{CompilationError, CompilationErrorKind, ErrorKind};
+use alloc::{format, string::String, vec::Vec};
+
+/// Maps byte offsets in the source to line/column positions.
+pub struct LineIndex<'a> {
+    source: &'a str,
+    /// byte offset of each line start
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(source: &'a str) -> Self {
+        let newlines = source.match_indices('\n').map(|(i, _)| i + 1);
+        let line_starts = core::iter::once(0).chain(newlines).collect();
+        Self {
+            source,
+            line_starts,
+        }
+    }
+    /// Offsets out of the source are clamped to its end.
+    pub fn position(&self, offset: usize) -> DiagnosticPosition {
+        let offset = offset.min(self.source.len());
+        let line = self.line_starts.partition_point(|&s| s <= offset) - 1;
+        let start = self.line_starts[line];
+        let character = match self.source.get(start..offset) {
+            Some(s) => s.encode_utf16().count(),
+            // offset inside a multibyte char
+            None => offset - start,
+        };
+        DiagnosticPosition {
+            line: line as u32,
+            character: character as u32,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DiagnosticPosition {
+    pub line: u32,
+    pub character: u32,
+}
+
+/// Values match LSP's DiagnosticSeverity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error = 1,
+    Warning = 2,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub start: DiagnosticPosition,
+    pub end: DiagnosticPosition,
+    pub severity: Severity,
+    /// name of the error kind, e.g. `VIfNoExpression`
+    pub code: String,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn new(error: &CompilationError, index: &LineIndex) -> Self {
+        let loc = &error.location;
+        let severity = if error.kind.is_warning() {
+            Severity::Warning
+        } else {
+            Severity::Error
+        };
+        Self {
+            start: index.position(loc.start.offset),
+            end: index.position(loc.end.offset),
+            severity,
+            code: error_code(&error.kind),
+            message: format!("{}", error),
+        }
+    }
+}
+
+fn error_code(kind: &CompilationErrorKind) -> String {
+    match kind {
+        CompilationErrorKind::ExtendPoint(err) => format!("{:?}", err),
+        kind => format!("{:?}", kind),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Position, SourceLocation};
+
+    fn error_at(kind: CompilationErrorKind, start: usize, end: usize) -> CompilationError {
+        let pos = |offset| Position {
+            offset,
+            line: 0,
+            column: 0,
+        };
+        let loc = SourceLocation {
+            start: pos(start),
+            end: pos(end),
+        };
+        CompilationError::new(kind).with_location(loc)
+    }
+
+    #[test]
+    fn test_line_index() {
+        let index = LineIndex::new("ab\ncd\n\n中😀x");
+        let pos = |line, character| DiagnosticPosition { line, character };
+        assert_eq!(index.position(0), pos(0, 0));
+        assert_eq!(index.position(2), pos(0, 2));
+        assert_eq!(index.position(3), pos(1, 0));
+        assert_eq!(index.position(6), pos(2, 0));
+        // 中 is one UTF-16 unit, 😀 is two
+        assert_eq!(index.position(14), pos(3, 3));
+        assert_eq!(index.position(100), pos(3, 4));
+    }
+
+    #[test]
+    fn test_error_to_diagnostic() {
+        let source = "<div>\n  <p v-if></p>\n</div>";
+        let start = source.find("v-if").unwrap();
+        let error = error_at(CompilationErrorKind::VIfNoExpression, start, start + 4);
+        let diagnostic = Diagnostic::new(&error, &LineIndex::new(source));
+        assert_eq!(diagnostic.start.line, 1);
+        assert_eq!(diagnostic.start.character, 5);
+        assert_eq!(diagnostic.end.character, 9);
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!(diagnostic.code, "VIfNoExpression");
+        assert_eq!(diagnostic.message, "v-if/v-else-if is missing expression.");
+    }
+
+    #[test]
+    fn test_warning_severity() {
+        let error = error_at(CompilationErrorKind::ConstantInterpolation, 0, 0);
+        let diagnostic = Diagnostic::new(&error, &LineIndex::new(""));
+        assert_eq!(diagnostic.severity, Severity::Warning);
+    }
+}
@@ -7,7 +7,8 @@
 use bitflags::bitflags;
 #[cfg(feature = "serde")]
 use serde::Serialize;
-use std::fmt;
+use core::{fmt, iter::FromIterator};
+use alloc::{format, string::String, vec::Vec};
 
 bitflags! {
     #[derive(Default, PartialEq, Eq, Clone, Copy)]
@@ -94,7 +95,7 @@ bitflags! {
 }
 
 impl fmt::Display for PatchFlag {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if let Some(name) = self.iter_names().next() {
             f.write_str(name.0)
         } else {
@@ -184,9 +185,24 @@ impl RuntimeHelper {
     pub const IS_MEMO_SAME: RH = RH(39);
 
     pub const INTERNAL_MAX: u8 = 40;
+    /// prefix of helper's local alias, e.g. `_openBlock`
+    pub const ALIAS_PREFIX: &'static str = "_";
 
+    /// Returns helper name, resolving platform helpers by map.
     pub fn helper_str(&self, map: &[&'static str]) -> &'static str {
-        match *self {
+        self.name()
+            .unwrap_or_else(|| map[(self.0 - RH::INTERNAL_MAX) as usize])
+    }
+    /// Returns helper's local alias, e.g. `_createElementVNode`.
+    /// `prefix` must match CodeGenerateOption's helper_prefix.
+    pub fn helper_alias(&self, map: &[&'static str], prefix: &str) -> String {
+        format!("{}{}", prefix, self.helper_str(map))
+    }
+
+    /// Returns core helper's canonical runtime function name.
+    /// Platform helpers return None, use `helper_str` for them instead.
+    pub fn name(&self) -> Option<&'static str> {
+        let name = match *self {
             RH::FRAGMENT => "Fragment",
             RH::TELEPORT => "Teleport",
             RH::SUSPENSE => "Suspense",
@@ -226,8 +242,9 @@ impl RuntimeHelper {
             RH::IS_REF => "isRef",
             RH::WITH_MEMO => "withMemo",
             RH::IS_MEMO_SAME => "isMemoSame",
-            RH(s) => map[(s - RH::INTERNAL_MAX) as usize],
-        }
+            _ => return None,
+        };
+        Some(name)
     }
 }
 #[cfg(feature = "serde")]
@@ -248,7 +265,7 @@ pub const HELPERS_IN_HOISTED: &[RH] = &[
     RH::CREATE_VNODE,
 ];
 
-#[derive(Clone, Default, PartialEq, Eq)]
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct HelperCollector(u64);
 impl HelperCollector {
@@ -258,12 +275,37 @@ impl HelperCollector {
     pub fn is_empty(&self) -> bool {
         self.0 == 0 || (cfg!(test) && self.0 == !0)
     }
+    pub fn len(&self) -> usize {
+        self.clone().into_iter().len()
+    }
     pub fn collect(&mut self, helper: RuntimeHelper) {
         self.0 |= 1 << helper.0;
     }
     pub fn contains(&self, helper: RuntimeHelper) -> bool {
         (self.0 & (1 << helper.0)) != 0
     }
+    /// Returns (helper_name, imported_as) pairs for bundlers to generate imports.
+    /// `prefix` must match CodeGenerateOption's helper_prefix.
+    pub fn imports(
+        &self,
+        helper_strs: &[&'static str],
+        prefix: &str,
+    ) -> Vec<(&'static str, String)> {
+        self.clone()
+            .into_iter()
+            .map(|rh| {
+                (
+                    rh.helper_str(helper_strs),
+                    rh.helper_alias(helper_strs, prefix),
+                )
+            })
+            .collect()
+    }
+    /// Returns helpers collected by both, e.g. used helpers in a deny list.
+    #[must_use]
+    pub fn intersect(&self, other: &Self) -> Self {
+        Self(self.0 & other.0)
+    }
     #[must_use]
     pub fn hoist_helpers(&self) -> Self {
         let mut n = Self(0);
@@ -301,6 +343,16 @@ impl Iterator for HelperIter {
 }
 impl ExactSizeIterator for HelperIter {}
 
+impl FromIterator<RuntimeHelper> for HelperCollector {
+    fn from_iter<I: IntoIterator<Item = RuntimeHelper>>(iter: I) -> Self {
+        let mut collector = Self::new();
+        for rh in iter {
+            collector.collect(rh);
+        }
+        collector
+    }
+}
+
 impl IntoIterator for HelperCollector {
     type Item = RuntimeHelper;
     type IntoIter = HelperIter;
@@ -308,3 +360,34 @@ impl IntoIterator for HelperCollector {
         HelperIter(self.0)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_helper_name() {
+        let cases = [
+            (RH::CREATE_ELEMENT_VNODE, "createElementVNode"),
+            (RH::OPEN_BLOCK, "openBlock"),
+            (RH::TO_DISPLAY_STRING, "toDisplayString"),
+            (RH::FRAGMENT, "Fragment"),
+            (RH::NORMALIZE_CLASS, "normalizeClass"),
+        ];
+        for (rh, name) in cases {
+            assert_eq!(rh.name(), Some(name));
+            assert_eq!(rh.helper_alias(&[], "_"), format!("_{}", name));
+            assert_eq!(rh.helper_alias(&[], "$"), format!("${}", name));
+        }
+    }
+
+    #[test]
+    fn test_platform_helper_str() {
+        let platform = RH(RH::INTERNAL_MAX + 1);
+        let map = ["vShow", "vModelText"];
+        assert_eq!(platform.helper_str(&map), "vModelText");
+        assert_eq!(platform.helper_alias(&map, "_"), "_vModelText");
+        assert_eq!(platform.name(), None);
+        assert_eq!(RH::OPEN_BLOCK.helper_str(&["vShow"]), "openBlock");
+    }
+}
@@ -1,10 +1,11 @@
 use crate::{
     flags::{PatchFlag, RuntimeHelper, SlotFlag, StaticLevel},
-    util::VStr,
-    Name,
+    util::{is_simple_identifier, VStr},
+    Name, SourceLocation,
 };
 use rustc_hash::FxHashSet;
-use std::hash::Hash;
+use smallvec::SmallVec;
+use std::{fmt, hash::Hash};
 
 #[cfg(feature = "serde")]
 use serde::Serialize;
@@ -61,6 +62,8 @@ pub struct TextIR<T: ConvertInfo> {
     pub fast_path: bool,  // without createTextCall
     pub need_patch: bool, // PatchFlag::TEXT
     pub texts: T::TextType,
+    /// source location of each text, used for diagnostics
+    pub locs: SmallVec<[SourceLocation; 1]>,
 }
 #[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct IfNodeIR<T: ConvertInfo> {
@@ -115,27 +118,33 @@ enum HoistedType<T: ConvertInfo> {
 #[derive(Default)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct HoistedAssets<T: ConvertInfo> {
-    hoisted: Vec<HoistedType<T>>,
+    // at most three entries, boxed slice saves a word in every vnode
+    hoisted: Box<[HoistedType<T>]>,
 }
 
 impl<T: ConvertInfo> HoistedAssets<T> {
+    fn push(&mut self, h: HoistedType<T>) {
+        let mut hoisted = std::mem::take(&mut self.hoisted).into_vec();
+        hoisted.push(h);
+        self.hoisted = hoisted.into_boxed_slice();
+    }
     pub fn add_props(&mut self, index: T::HoistedIndex) {
         debug_assert! {
             !self.hoisted.iter().any(|n| matches!(n, HoistedType::Props(_)))
         };
-        self.hoisted.push(HoistedType::Props(index));
+        self.push(HoistedType::Props(index));
     }
     pub fn add_dynamic_props(&mut self, index: T::HoistedIndex) {
         debug_assert! {
             !self.hoisted.iter().any(|n| matches!(n, HoistedType::DynamicProps(_)))
         };
-        self.hoisted.push(HoistedType::DynamicProps(index));
+        self.push(HoistedType::DynamicProps(index));
     }
     pub fn add_children(&mut self, index: T::HoistedIndex) {
         debug_assert! {
             !self.hoisted.iter().any(|n| matches!(n, HoistedType::Children(_)))
         };
-        self.hoisted.push(HoistedType::Children(index));
+        self.push(HoistedType::Children(index));
     }
     pub fn has_children_hoisted(&self) -> Option<&T::HoistedIndex> {
         self.hoisted.iter().find_map(|h| {
@@ -179,6 +188,9 @@ pub struct VNodeIR<T: ConvertInfo> {
     pub disable_tracking: bool,
     pub is_component: bool,
     pub hoisted: HoistedAssets<T>,
+    /// source location of the element, empty for compiler generated vnode.
+    /// DynamicPropsWarner reports here and emit_ir_json serializes it.
+    pub loc: Box<SourceLocation>,
 }
 #[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct Slot<T: ConvertInfo> {
@@ -269,6 +281,8 @@ pub enum JsExpr<'a> {
     },
     /// alternative to join string as JsExpr
     Compound(Vec<JsExpr<'a>>),
+    /// object literal. StrLit keys are quoted unless the raw name is a valid
+    /// identifier, so camelized `my-prop` is still written as `"myProp"`.
     Props(Vec<Prop<'a>>),
     /// for calling runtime helper, e.g. resolveComponent()
     Call(RuntimeHelper, Vec<JsExpr<'a>>),
@@ -292,6 +306,15 @@ impl<'a> JsExpr<'a> {
     pub fn str_lit<V: Into<VStr<'a>>>(v: V) -> Self {
         JsExpr::StrLit(v.into())
     }
+    /// placeholder comment for v-if without v-else branch.
+    /// It is created as block so diffing has a stable anchor.
+    pub fn v_if_comment(is_dev: bool) -> Self {
+        let s = if is_dev { "v-if" } else { "" };
+        JsExpr::Call(
+            RuntimeHelper::CREATE_COMMENT,
+            vec![JsExpr::str_lit(s), JsExpr::Src("true")],
+        )
+    }
     pub fn func<V: Into<VStr<'a>>>(v: V) -> Self {
         Self::FuncSimple {
             src: v.into(),
@@ -315,6 +338,67 @@ impl<'a> JsExpr<'a> {
     }
 }
 
+/// Approximate JS source in one line, for inspecting IR in development.
+/// Platform helpers are shown by their index since names need the platform map.
+impl<'a> fmt::Debug for JsExpr<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use JsExpr::*;
+        let list = |f: &mut fmt::Formatter<'_>, es: &[JsExpr]| {
+            for (i, e) in es.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{:?}", e)?;
+            }
+            Ok(())
+        };
+        match self {
+            Src(s) | Param(s) => f.write_str(s),
+            Num(n) => write!(f, "{}", n),
+            StrLit(l) => {
+                let mut l = *l;
+                l.be_js_str().write_to(f)
+            }
+            Simple(e, _) | FuncSimple { src: e, .. } => e.write_to(f),
+            Symbol(h) => f.write_str(&debug_helper(*h)),
+            Compound(v) | FuncCompound { body: v, .. } => v.iter().try_for_each(|e| e.fmt(f)),
+            Array(a) => {
+                f.write_str("[")?;
+                list(f, a)?;
+                f.write_str("]")
+            }
+            Call(h, args) => {
+                write!(f, "{}(", debug_helper(*h))?;
+                list(f, args)?;
+                f.write_str(")")
+            }
+            Props(ps) if ps.is_empty() => f.write_str("{}"),
+            Props(ps) => {
+                f.write_str("{ ")?;
+                for (i, (k, v)) in ps.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    match k {
+                        StrLit(k) if is_simple_identifier(*k) => k.write_to(&mut *f)?,
+                        StrLit(_) => write!(f, "{:?}", k)?,
+                        _ => write!(f, "[{:?}]", k)?,
+                    }
+                    write!(f, ": {:?}", v)?;
+                }
+                f.write_str(" }")
+            }
+        }
+    }
+}
+
+fn debug_helper(h: RuntimeHelper) -> String {
+    match h.name() {
+        Some(name) => format!("{}{}", RuntimeHelper::ALIAS_PREFIX, name),
+        None => format!("_helper{}", h.0),
+    }
+}
+
 fn vec_static_level(v: &[JsExpr]) -> StaticLevel {
     v.iter()
         .map(JsExpr::static_level)
@@ -340,3 +424,36 @@ fn call_static_level(rh: &RuntimeHelper, v: &[JsExpr]) -> StaticLevel {
         StaticLevel::NotStatic
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use JsExpr as Js;
+
+    #[test]
+    fn test_debug_js_expr() {
+        let call = Js::Call(
+            RuntimeHelper::TO_DISPLAY_STRING,
+            vec![Js::simple("msg"), Js::Num(1)],
+        );
+        let compound = Js::Compound(vec![Js::str_lit("a"), Js::Src(" + "), call]);
+        assert_eq!(
+            format!("{:?}", compound),
+            r#""a" + _toDisplayString(msg, 1)"#
+        );
+        let props = Js::Props(vec![
+            (Js::str_lit("id"), Js::simple("a")),
+            (
+                Js::str_lit("data-x"),
+                Js::Array(vec![Js::Num(1), Js::Num(2)]),
+            ),
+            (Js::simple("key"), Js::Props(vec![])),
+        ]);
+        assert_eq!(
+            format!("{:?}", props),
+            r#"{ id: a, "data-x": [1, 2], [key]: {} }"#
+        );
+        assert_eq!(format!("{:?}", Js::func("a++")), "a++");
+        assert_eq!(format!("{:?}", Js::Symbol(RuntimeHelper(50))), "_helper50");
+    }
+}
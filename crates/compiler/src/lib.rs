@@ -1,25 +1,43 @@
 #![allow(dead_code)]
+#![cfg_attr(not(feature = "std"), no_std)]
 //! See README.md
+//! Without the default `std` feature, only scanner and parser are available.
+
+extern crate alloc;
 
 // TODO: reorg pub
 #[macro_use]
 pub mod util;
+#[cfg(feature = "std")]
 pub mod codegen;
+#[cfg(feature = "std")]
 pub mod compiler;
+#[cfg(feature = "std")]
 pub mod converter;
 pub mod error;
 pub mod flags;
+#[cfg(feature = "std")]
 pub mod ir;
 pub mod parser;
 pub mod scanner;
+#[cfg(all(feature = "std", feature = "serde", any(test, feature = "testing")))]
+pub mod testing;
+pub mod tokenizer;
+#[cfg(feature = "std")]
 pub mod transformer;
 
+use core::ops::Range;
+#[cfg(feature = "std")]
 use flags::StaticLevel;
+#[cfg(feature = "std")]
 pub use ir::JsExpr as Js;
+#[cfg(feature = "std")]
 use rustc_hash::FxHashMap;
+#[cfg(feature = "std")]
 use std::ops::Deref;
-use std::ops::Range;
+#[cfg(feature = "std")]
 pub use transformer::{pass::Chain, process_expression::ExpressionProcessor};
+#[cfg(feature = "std")]
 use util::VStr;
 
 #[cfg(feature = "serde")]
@@ -45,6 +63,7 @@ impl Serialize for Position {
     where
         S: serde::Serializer,
     {
+        use alloc::format;
         let s = format!(
             // Position, Line, Column
             "Pos: {}, Ln: {}, Col: {}",
@@ -108,6 +127,7 @@ pub enum BindingTypes {
     Options,
 }
 
+#[cfg(feature = "std")]
 impl BindingTypes {
     pub fn get_js_prop<'a>(&self, name: VStr<'a>, lvl: StaticLevel) -> Js<'a> {
         use BindingTypes::*;
@@ -122,10 +142,12 @@ impl BindingTypes {
     }
 }
 
+#[cfg(feature = "std")]
 /// stores binding variables exposed by data/prop/setup script.
 /// also stores if the binding is from setup script.
 #[derive(Default, Clone)]
 pub struct BindingMetadata<'a>(FxHashMap<&'a str, BindingTypes>, bool);
+#[cfg(feature = "std")]
 impl<'a> BindingMetadata<'a> {
     fn new(map: FxHashMap<&'a str, BindingTypes>, from_setup: bool) -> Self {
         Self(map, from_setup)
@@ -140,6 +162,7 @@ impl<'a> BindingMetadata<'a> {
         self.1
     }
 }
+#[cfg(feature = "std")]
 impl<'a> Deref for BindingMetadata<'a> {
     type Target = FxHashMap<&'a str, BindingTypes>;
     fn deref(&self) -> &Self::Target {
@@ -147,6 +170,7 @@ impl<'a> Deref for BindingMetadata<'a> {
     }
 }
 
+#[cfg(feature = "std")]
 /// SFC info of the current template
 pub struct SFCInfo<'a> {
     /// Compile the function for inlining inside setup().
@@ -167,6 +191,7 @@ pub struct SFCInfo<'a> {
     pub self_name: String,
 }
 
+#[cfg(feature = "std")]
 impl<'a> Default for SFCInfo<'a> {
     fn default() -> Self {
         Self {
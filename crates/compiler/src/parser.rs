@@ -7,7 +7,7 @@
 // 3. create an element for a token: For custom component
 //    N/A. We don't handle JS execution for custom component.
 // 4. adjust MathML/SVG attributes:
-//    ?? Should we handle this? The original Vue compiler does not.
+//    N/A. Attribute names keep source casing, so SVG's viewBox needs no adjustment.
 // 5. Inserting Text/Comment: N/A. We don't handle script/insertion location.
 // 6. Parsing elements that contain only text: Already handled in scanner.
 // 7. Closing elements that have implied end tags:
@@ -18,11 +18,12 @@ use super::{
     error::{CompilationError, CompilationErrorKind as ErrorKind, RcErrHandle},
     flags::RuntimeHelper,
     scanner::{Attribute, AttributeValue, Tag, TextMode, Token, TokenSource},
-    util::{find_dir, is_core_component, no, non_whitespace, yes, VStr},
+    util::{find_dir, find_dir_empty, is_core_component, no, non_whitespace, yes, VStr},
     Name, Namespace, SourceLocation,
 };
+use alloc::{collections::BTreeSet, string::String, vec, vec::Vec};
+use core::ops::Deref;
 use smallvec::{smallvec, SmallVec};
-use std::ops::Deref;
 
 #[cfg(feature = "serde")]
 use serde::Serialize;
@@ -48,6 +49,12 @@ impl<'a> AstNode<'a> {
             _ => None,
         }
     }
+    pub fn get_comment(&self) -> Option<&SourceNode<'a>> {
+        match self {
+            AstNode::Comment(c) => Some(c),
+            _ => None,
+        }
+    }
     pub fn into_element(self) -> Element<'a> {
         match self {
             AstNode::Element(e) => e,
@@ -70,6 +77,37 @@ pub struct SourceNode<'a> {
     pub location: SourceLocation,
 }
 
+impl<'a> SourceNode<'a> {
+    /// Comment like `<!-- @vue-ignore -->` used as a compilation marker.
+    pub fn is_marker(&self, marker: &str) -> bool {
+        self.source.trim() == marker
+    }
+}
+
+/// Elements preceded by a marker comment, whitespace text in between
+/// is skipped. Comments must be preserved in parsing for markers to work.
+pub fn marked_elements<'a, 'b>(
+    children: &'b [AstNode<'a>],
+    marker: &'b str,
+) -> impl Iterator<Item = &'b Element<'a>> {
+    let mut marked = false;
+    children.iter().filter_map(move |n| match n {
+        AstNode::Comment(c) => {
+            marked = c.is_marker(marker);
+            None
+        }
+        AstNode::Text(t) if t.is_all_whitespace() => None,
+        AstNode::Element(e) if marked => {
+            marked = false;
+            Some(e)
+        }
+        _ => {
+            marked = false;
+            None
+        }
+    })
+}
+
 pub struct TextNode<'a> {
     pub text: SmallVec<[VStr<'a>; 1]>,
     pub location: SourceLocation,
@@ -83,7 +121,7 @@ impl<'a> Serialize for TextNode<'a> {
         use serde::ser::SerializeStruct;
         let mut state = serializer.serialize_struct("TextNode", 2)?;
         let s = self.text.iter().map(|&s| s.into_string());
-        let s: String = s.collect();
+        let s: alloc::string::String = s.collect();
         state.serialize_field("text", &s)?;
         state.serialize_field("location", &self.location)?;
         state.end()
@@ -220,21 +258,115 @@ pub struct AstRoot<'a> {
     pub location: SourceLocation,
 }
 
-#[derive(Clone, Default)]
+/// Returned by Visitor methods to control the AST walk.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VisitControl {
+    Continue,
+    /// do not visit the element's properties and children
+    SkipChildren,
+    /// abort the whole walk
+    Stop,
+}
+
+/// Read only visitor over the parsed AST, for linters/codemods.
+/// Unlike CorePass it runs before conversion, so it sees raw nodes.
+pub trait Visitor<'a> {
+    fn visit_element(&mut self, _: &Element<'a>) -> VisitControl {
+        VisitControl::Continue
+    }
+    fn visit_text(&mut self, _: &TextNode<'a>) -> VisitControl {
+        VisitControl::Continue
+    }
+    fn visit_interpolation(&mut self, _: &SourceNode<'a>) -> VisitControl {
+        VisitControl::Continue
+    }
+    fn visit_comment(&mut self, _: &SourceNode<'a>) -> VisitControl {
+        VisitControl::Continue
+    }
+    fn visit_attribute(&mut self, _: &Attribute<'a>) -> VisitControl {
+        VisitControl::Continue
+    }
+    fn visit_directive(&mut self, _: &Directive<'a>) -> VisitControl {
+        VisitControl::Continue
+    }
+}
+
+/// Walks node in pre-order. Returns Stop if the visitor aborted the walk.
+pub fn walk<'a, V: Visitor<'a>>(node: &AstNode<'a>, visitor: &mut V) -> VisitControl {
+    use VisitControl as VC;
+    let e = match node {
+        AstNode::Element(e) => e,
+        AstNode::Text(t) => return stop_or_continue(visitor.visit_text(t)),
+        AstNode::Interpolation(i) => return stop_or_continue(visitor.visit_interpolation(i)),
+        AstNode::Comment(c) => return stop_or_continue(visitor.visit_comment(c)),
+    };
+    match visitor.visit_element(e) {
+        VC::Continue => (),
+        VC::SkipChildren => return VC::Continue,
+        VC::Stop => return VC::Stop,
+    }
+    for prop in e.properties.iter() {
+        let ctrl = match prop {
+            ElemProp::Attr(a) => visitor.visit_attribute(a),
+            ElemProp::Dir(d) => visitor.visit_directive(d),
+        };
+        if ctrl == VC::Stop {
+            return VC::Stop;
+        }
+    }
+    for child in e.children.iter() {
+        if walk(child, visitor) == VC::Stop {
+            return VC::Stop;
+        }
+    }
+    VC::Continue
+}
+
+// leaf nodes have no children to skip
+fn stop_or_continue(ctrl: VisitControl) -> VisitControl {
+    if ctrl == VisitControl::Stop {
+        ctrl
+    } else {
+        VisitControl::Continue
+    }
+}
+
+#[derive(Clone, Default, Hash)]
 pub enum WhitespaceStrategy {
     Preserve,
     #[default]
     Condense,
 }
 
+/// Mirrors vue-next's ParseMode. It decides which tags can self-close
+/// and how end tags are matched. Whitespace is controlled by WhitespaceStrategy.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ParseMode {
+    /// XML-like. All tags can self-close and tag names are case sensitive.
+    /// is_void_tag is not consulted.
+    Base,
+    /// Only void tags close themselves. `/>` on other tags is ignored.
+    /// End tags are matched case-insensitively.
+    Html,
+    /// Same as Html but all tags can self-close, which is common in SFC templates.
+    #[default]
+    Sfc,
+}
+
 // `is_xxx` methods in ParseOption targets different audience.
 // Please refer to project README for more details.
 #[derive(Clone)]
 pub struct ParseOption {
+    pub parse_mode: ParseMode,
     pub whitespace: WhitespaceStrategy,
     pub preserve_comment: bool,
     pub get_namespace: fn(&str, Option<&Element<'_>>) -> Namespace,
     pub get_text_mode: fn(&str) -> TextMode,
+    /// Extra tags whose content is taken verbatim. Must match ScanOption.
+    pub raw_elements: BTreeSet<String>,
+    /// Interpolation delimiters, used to report them in static attributes.
+    /// Must match ScanOption.
+    pub delimiters: (String, String),
     /// Returns if a tag is self closing.
     pub is_void_tag: fn(&str) -> bool,
     // probably we don't need configure pre tag?
@@ -247,20 +379,40 @@ pub struct ParseOption {
     pub get_builtin_component: fn(&str) -> Option<RuntimeHelper>,
     /// For platform developer. Registers platform components written in host language like C++.
     pub is_native_element: fn(&str) -> bool,
+    /// Elements nested deeper than this are dropped with NestingTooDeep error.
+    /// It guards recursive conversion/transformation against untrusted input.
+    pub max_depth: usize,
+    /// Treat elements with removed `v-is` as components. Must match ConvertOption.
+    pub compat_v_is: bool,
+}
+
+impl ParseOption {
+    pub fn text_mode(&self, tag: &str) -> TextMode {
+        if self.raw_elements.contains(tag) {
+            TextMode::RawText
+        } else {
+            (self.get_text_mode)(tag)
+        }
+    }
 }
 
 impl Default for ParseOption {
     fn default() -> Self {
         Self {
+            parse_mode: ParseMode::Sfc,
             whitespace: WhitespaceStrategy::Condense,
             preserve_comment: true,
             get_namespace: |_, _| Namespace::Html,
             get_text_mode: |_| TextMode::Data,
+            raw_elements: BTreeSet::new(),
+            delimiters: ("{{".into(), "}}".into()),
             is_void_tag: no,
             is_pre_tag: |s| s == "pre",
             is_custom_element: no,
             get_builtin_component: |_| None,
             is_native_element: yes,
+            max_depth: 128,
+            compat_v_is: false,
         }
     }
 }
@@ -274,6 +426,8 @@ impl Parser {
         Self { option }
     }
 
+    /// Build an AST from tokens. Parsing never panics on any input:
+    /// malformed templates are recovered from and reported via err_handle.
     pub fn parse<'a, Ts>(&self, tokens: Ts, err_handle: RcErrHandle) -> AstRoot<'a>
     where
         Ts: TokenSource<'a>,
@@ -288,6 +442,7 @@ impl Parser {
             pre_count: 0,
             v_pre_index: None,
             need_flag_namespace,
+            dropped_depth: 0,
         }
         .build_ast()
     }
@@ -309,6 +464,8 @@ where
     // NB: idx is enough since v-pre does not nest
     v_pre_index: Option<usize>,
     need_flag_namespace: bool,
+    // how many open elements are dropped for exceeding max_depth
+    dropped_depth: usize,
 }
 
 // utility method
@@ -340,6 +497,7 @@ where
 {
     fn build_ast(mut self) -> AstRoot<'a> {
         let start = self.tokens.current_position();
+        self.set_scanner_flag();
         while let Some(token) = self.tokens.next() {
             self.parse_token(token);
         }
@@ -374,6 +532,9 @@ where
             self_closing,
             attributes,
         } = tag;
+        if self.open_elems.len() >= self.option.max_depth {
+            return self.drop_too_deep(name, self_closing);
+        }
         let props = self.parse_attributes(attributes);
         let ns = (self.option.get_namespace)(name, self.open_elems.last());
         let elem = Element {
@@ -387,7 +548,7 @@ where
                 end: self.tokens.current_position(),
             },
         };
-        if self_closing || (self.option.is_void_tag)(name) {
+        if self.closes_itself(name, self_closing) {
             let node = self.parse_element(elem);
             self.insert_node(node);
         } else {
@@ -421,11 +582,23 @@ where
                     // TODO: report duplicate prop by is_mergeable_prop
                     ElemProp::Dir(dir_parser.parse(attr))
                 } else {
+                    self.check_attr_interpolation(&attr);
                     ElemProp::attr(attr)
                 }
             })
             .collect()
     }
+    // `id="{{ x }}"` is a literal string, the user probably meant `:id="x"`
+    fn check_attr_interpolation(&self, attr: &Attribute<'a>) {
+        let val = match &attr.value {
+            Some(v) => v,
+            None => return,
+        };
+        if val.content.contains(&*self.option.delimiters.0) {
+            let kind = ErrorKind::UnexpectedInterpolationInAttribute;
+            self.emit_error(kind, val.location.clone());
+        }
+    }
 
     fn handle_pre_like(&mut self, elem: &Element) {
         debug_assert!(
@@ -444,13 +617,29 @@ where
             self.v_pre_index = Some(self.open_elems.len());
         }
     }
+    // Children of dropped elements are flattened into the deepest element.
+    fn drop_too_deep(&mut self, name: &'a str, self_closing: bool) {
+        if self.dropped_depth == 0 {
+            let start = self.tokens.last_position();
+            let loc = self.tokens.get_location_from(start);
+            self.emit_error(ErrorKind::NestingTooDeep, loc);
+        }
+        if !self.closes_itself(name, self_closing) {
+            self.dropped_depth += 1;
+        }
+    }
     fn parse_end_tag(&mut self, end_tag: &'a str) {
+        // end tag of a dropped element
+        if self.dropped_depth > 0 {
+            self.dropped_depth -= 1;
+            return;
+        }
         // rfind is good since only mismatch will traverse stack
         let index = self
             .open_elems
             .iter()
             .enumerate()
-            .rfind(|p| element_matches_end_tag(p.1, end_tag))
+            .rfind(|p| self.element_matches_end_tag(p.1, end_tag))
             .map(|p| p.0);
         if let Some(i) = index {
             let mut to_close = self.open_elems.len() - i;
@@ -481,7 +670,7 @@ where
         elem.location = location;
         if self.pre_count > 0 {
             self.decrement_pre(&mut elem)
-        } else if (self.option.get_text_mode)(elem.tag_name) == TextMode::Data {
+        } else if self.option.text_mode(elem.tag_name) == TextMode::Data {
             // skip compress in pre or RAWTEXT/RCDATA
             compress_whitespaces(&mut elem.children, self.need_condense());
         }
@@ -540,9 +729,20 @@ where
             }
         }
         let end = self.tokens.last_position();
-        let location = SourceLocation { start, end };
-        let text_node = TextNode { text, location };
-        self.insert_node(AstNode::Text(text_node));
+        let siblings = match self.open_elems.last_mut() {
+            Some(elem) => &mut elem.children,
+            None => &mut self.root_nodes,
+        };
+        // a dropped token (e.g. invalid end tag) can separate two text runs.
+        // merge them to keep the no-consecutive-text invariant.
+        if let Some(AstNode::Text(prev)) = siblings.last_mut() {
+            prev.text.extend(text);
+            prev.location.end = end;
+        } else {
+            let location = SourceLocation { start, end };
+            let text_node = TextNode { text, location };
+            self.insert_node(AstNode::Text(text_node));
+        }
         // NB: token must not be dropped
         if let Some(token) = next_token {
             self.parse_token(token);
@@ -598,9 +798,13 @@ where
     // must call this when handle CDATA
     #[inline]
     fn set_scanner_flag(&mut self) {
-        if self.need_flag_namespace {
+        if !self.need_flag_namespace {
             return;
         }
+        // base mode is XML-like: CDATA is always raw text there.
+        if self.option.parse_mode == ParseMode::Base {
+            return self.tokens.set_is_in_html(false);
+        }
         // TODO: we can set flag only when namespace changes
         let in_html = self
             .open_elems
@@ -624,7 +828,7 @@ where
             return true;
         }
         e.properties.iter().any(|prop| match prop {
-            ElemProp::Dir(Directive { name: "is", .. }) => true,
+            ElemProp::Dir(Directive { name: "is", .. }) => opt.compat_v_is,
             ElemProp::Attr(Attribute {
                 name: "is",
                 value: Some(v),
@@ -637,6 +841,23 @@ where
     fn need_condense(&self) -> bool {
         matches!(self.option.whitespace, WhitespaceStrategy::Condense)
     }
+
+    fn closes_itself(&self, tag_name: &str, self_closing: bool) -> bool {
+        let is_void = || (self.option.is_void_tag)(tag_name);
+        match self.option.parse_mode {
+            ParseMode::Base => self_closing,
+            ParseMode::Html => is_void(),
+            ParseMode::Sfc => self_closing || is_void(),
+        }
+    }
+
+    fn element_matches_end_tag(&self, e: &Element, tag: &str) -> bool {
+        if self.option.parse_mode == ParseMode::Base {
+            e.tag_name == tag
+        } else {
+            e.tag_name.eq_ignore_ascii_case(tag)
+        }
+    }
 }
 
 const BIND_CHAR: char = ':';
@@ -785,7 +1006,7 @@ impl<'a, 'b> DirectiveParser<'a, 'b> {
         }
         Some(if !arg.starts_with('[') {
             DirectiveArg::Static(arg)
-        } else if let Some(i) = arg.chars().position(|c| c == ']') {
+        } else if let Some(i) = arg.find(']') {
             debug_assert!(i == arg.len() - 1);
             DirectiveArg::Dynamic(&arg[1..i])
         } else {
@@ -807,7 +1028,7 @@ impl<'a, 'b> DirectiveParser<'a, 'b> {
             mods[1..]
                 .as_bytes()
                 .split(|b| *b == b'.')
-                .map(std::str::from_utf8) // use unsafe if too slow
+                .map(core::str::from_utf8) // use unsafe if too slow
                 .map(Result::unwrap)
                 .inspect(report_missing_mod)
                 .collect()
@@ -860,12 +1081,13 @@ fn compress_whitespaces(nodes: &mut Vec<AstNode>, need_condense: bool) {
             } else if !need_condense {
                 false
             } else {
-                // Condense mode remove whitespaces between comment and
+                // Condense mode remove whitespaces next to comments and
                 // whitespaces with contains newline between two elements
                 let prev = &nodes[i - 1];
                 let next = &nodes[i + 1];
                 match (prev, next) {
                     (A::Comment(_), A::Comment(_)) => true,
+                    (A::Comment(_), n) | (n, A::Comment(_)) => is_element(n),
                     _ => is_element(prev) && is_element(next) && child.contains(&['\r', '\n'][..]),
                 }
             }
@@ -907,11 +1129,8 @@ fn is_special_template_directive(n: &str) -> bool {
 }
 
 fn is_template_element(e: &Element) -> bool {
-    e.tag_name == "template" && find_dir(e, is_special_template_directive).is_some()
-}
-
-fn element_matches_end_tag(e: &Element, tag: &str) -> bool {
-    e.tag_name.eq_ignore_ascii_case(tag)
+    // v-else and v-slot shorthand have no expression
+    e.tag_name == "template" && find_dir_empty(e, is_special_template_directive).is_some()
 }
 
 fn is_v_pre_boundary(elem: &Element) -> bool {
@@ -951,6 +1170,299 @@ pub mod test {
         assert_eq!(val.into_string(), "&");
     }
 
+    #[test]
+    fn test_namespaced_attr() {
+        // xml:/xlink: names never start with a directive prefix, so they stay static
+        let mut a = mock_element("<a xml:lang='en' xlink:href='#a'/>");
+        assert_eq!(a.properties.len(), 2);
+        let lang = cast!(a.properties.remove(0), ElemProp::Attr);
+        assert_eq!(lang.name, "xml:lang");
+        assert_eq!(lang.value.unwrap().content.into_string(), "en");
+        let href = cast!(a.properties.remove(0), ElemProp::Attr);
+        assert_eq!(href.name, "xlink:href");
+    }
+
+    #[test]
+    fn test_custom_element() {
+        let parse = |option: ParseOption| {
+            let tokens = base_scan("<my-widget/>");
+            let eh = std::rc::Rc::new(TestErrorHandler);
+            let mut ast = Parser::new(option).parse(tokens, eh);
+            ast.children.remove(0).into_element()
+        };
+        let is_native_element = |s: &str| !s.contains('-');
+        let elem = parse(ParseOption {
+            is_native_element,
+            is_custom_element: |s| s.contains('-'),
+            ..Default::default()
+        });
+        assert!(elem.tag_type == ElementType::Plain);
+        let elem = parse(ParseOption {
+            is_native_element,
+            ..Default::default()
+        });
+        assert!(elem.tag_type == ElementType::Component);
+    }
+
+    #[test]
+    fn test_svg_attr_casing() {
+        let tokens = base_scan("<svg viewBox='0 0 10 10' preserveAspectRatio='none'/>");
+        let parser = Parser::new(ParseOption {
+            get_namespace: |tag, _| {
+                if tag == "svg" {
+                    Namespace::Svg
+                } else {
+                    Namespace::Html
+                }
+            },
+            ..Default::default()
+        });
+        let eh = std::rc::Rc::new(TestErrorHandler);
+        let mut svg = parser.parse(tokens, eh).children.remove(0).into_element();
+        assert!(svg.namespace == Namespace::Svg);
+        let view_box = cast!(svg.properties.remove(0), ElemProp::Attr);
+        assert_eq!(view_box.name, "viewBox");
+        let ratio = cast!(svg.properties.remove(0), ElemProp::Attr);
+        assert_eq!(ratio.name, "preserveAspectRatio");
+    }
+
+    #[test]
+    fn test_raw_elements() {
+        use crate::scanner::{ScanOption, Scanner};
+        let raw_elements: BTreeSet<_> = core::iter::once("docs".into()).collect();
+        let scanner = Scanner::new(ScanOption {
+            raw_elements: raw_elements.clone(),
+            ..Default::default()
+        });
+        let parser = Parser::new(ParseOption {
+            raw_elements,
+            ..Default::default()
+        });
+        let case = "<docs>a < b  <p>{{ c }}</docs><p/>";
+        let eh = std::rc::Rc::new(TestErrorHandler);
+        let ast = parser.parse(scanner.scan(case, eh.clone()), eh);
+        assert_eq!(ast.children.len(), 2);
+        let docs = cast!(&ast.children[0], AstNode::Element);
+        assert_eq!(docs.children.len(), 1);
+        let text = cast!(&docs.children[0], AstNode::Text);
+        assert_eq!(text.text[0].raw, "a < b  <p>{{ c }}");
+    }
+
+    fn parse_with_mode(s: &str, parse_mode: ParseMode) -> AstRoot<'_> {
+        let parser = Parser::new(ParseOption {
+            parse_mode,
+            is_void_tag: |s| s == "br",
+            ..Default::default()
+        });
+        let eh = std::rc::Rc::new(TestErrorHandler);
+        parser.parse(base_scan(s), eh)
+    }
+
+    #[test]
+    fn test_base_mode() {
+        let ast = parse_with_mode("<Foo/>text", ParseMode::Base);
+        assert_eq!(ast.children.len(), 2);
+        // void tag is not consulted in XML-like mode
+        let ast = parse_with_mode("<br>text", ParseMode::Base);
+        assert_eq!(ast.children.len(), 1);
+        // tag name is case sensitive
+        let ast = parse_with_mode("<Foo></foo>text", ParseMode::Base);
+        assert_eq!(ast.children.len(), 1);
+    }
+
+    #[test]
+    fn test_html_mode() {
+        let mut ast = parse_with_mode("<Foo/>text", ParseMode::Html);
+        assert_eq!(ast.children.len(), 1);
+        let foo = ast.children.remove(0).into_element();
+        assert!(matches!(foo.children[0], AstNode::Text(_)));
+        let ast = parse_with_mode("<br/>text", ParseMode::Html);
+        assert_eq!(ast.children.len(), 2);
+        let ast = parse_with_mode("<Foo></foo>text", ParseMode::Html);
+        assert_eq!(ast.children.len(), 2);
+    }
+
+    #[test]
+    fn test_sfc_mode() {
+        let ast = parse_with_mode("<Foo/>text", ParseMode::Sfc);
+        assert_eq!(ast.children.len(), 2);
+        let ast = parse_with_mode("<br>text", ParseMode::Sfc);
+        assert_eq!(ast.children.len(), 2);
+    }
+
+    fn parse_with_errors(s: &str) -> (AstRoot<'_>, Vec<CompilationError>) {
+        use crate::error::VecErrorHandler;
+        use crate::scanner::{ScanOption, Scanner};
+        let eh = std::rc::Rc::new(VecErrorHandler::new());
+        let tokens = Scanner::new(ScanOption::default()).scan(s, eh.clone());
+        let ast = Parser::new(ParseOption::default()).parse(tokens, eh.clone());
+        let errors = eh.error_mut().drain(..).collect();
+        (ast, errors)
+    }
+
+    #[test]
+    fn test_cdata_by_parse_mode() {
+        use crate::error::VecErrorHandler;
+        use crate::scanner::{ScanOption, Scanner};
+        let parse = |parse_mode| {
+            let eh = std::rc::Rc::new(VecErrorHandler::new());
+            let s = "<p><![CDATA[a < b]]></p>";
+            let tokens = Scanner::new(ScanOption::default()).scan(s, eh.clone());
+            let option = ParseOption {
+                parse_mode,
+                ..Default::default()
+            };
+            let ast = Parser::new(option).parse(tokens, eh.clone());
+            let errors: Vec<_> = eh.error_mut().drain(..).collect();
+            (ast, errors)
+        };
+        let (mut ast, errors) = parse(ParseMode::Base);
+        assert!(errors.is_empty());
+        let p = ast.children.remove(0).into_element();
+        let text = cast!(&p.children[0], AstNode::Text);
+        assert_eq!(text.text[0].raw, "a < b");
+        let (mut ast, errors) = parse(ParseMode::Html);
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0].kind, ErrorKind::CDataInHtmlContent));
+        let p = ast.children.remove(0).into_element();
+        let comment = cast!(&p.children[0], AstNode::Comment);
+        assert_eq!(comment.source, "[CDATA[a < b]]");
+    }
+
+    #[test]
+    fn test_skip_doctype() {
+        let (ast, errors) = parse_with_errors("<!DOCTYPE html>\n<div>hello</div>");
+        assert_eq!(ast.children.len(), 1);
+        assert_eq!(cast!(&ast.children[0], AstNode::Element).tag_name, "div");
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0].kind, ErrorKind::UnexpectedDoctype));
+        assert_eq!(errors[0].location.end.offset, 15);
+        let (ast, errors) = parse_with_errors("<!doctype html><p/>");
+        assert_eq!(ast.children.len(), 1);
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn test_skip_xml_declaration() {
+        let case = "<?xml version='1.0'?>\n<!DOCTYPE svg>\n<svg/>";
+        let (ast, errors) = parse_with_errors(case);
+        assert_eq!(ast.children.len(), 1);
+        assert_eq!(cast!(&ast.children[0], AstNode::Element).tag_name, "svg");
+        assert_eq!(errors.len(), 2);
+        assert!(errors
+            .iter()
+            .all(|e| matches!(e.kind, ErrorKind::UnexpectedDoctype)));
+        // only leading declarations are skipped
+        let (ast, _) = parse_with_errors("<p/><!DOCTYPE html>");
+        assert_eq!(ast.children.len(), 2);
+    }
+
+    #[derive(Default)]
+    struct Counter {
+        elements: usize,
+        directives: usize,
+        skip: Option<&'static str>,
+        stop: Option<&'static str>,
+    }
+    impl<'a> Visitor<'a> for Counter {
+        fn visit_element(&mut self, e: &Element<'a>) -> VisitControl {
+            self.elements += 1;
+            if self.stop == Some(e.tag_name) {
+                VisitControl::Stop
+            } else if self.skip == Some(e.tag_name) {
+                VisitControl::SkipChildren
+            } else {
+                VisitControl::Continue
+            }
+        }
+        fn visit_directive(&mut self, _: &Directive<'a>) -> VisitControl {
+            self.directives += 1;
+            VisitControl::Continue
+        }
+    }
+    fn count(s: &str, counter: &mut Counter) {
+        for child in base_parse(s).children.iter() {
+            if walk(child, counter) == VisitControl::Stop {
+                break;
+            }
+        }
+    }
+
+    #[test]
+    fn test_visitor() {
+        let case = "<div v-if='a'><p :id='b' @click='c'>{{d}}</p></div><span v-show='e'/>";
+        let mut counter = Counter::default();
+        count(case, &mut counter);
+        assert_eq!(counter.elements, 3);
+        assert_eq!(counter.directives, 4);
+        let mut counter = Counter {
+            skip: Some("div"),
+            ..Default::default()
+        };
+        count(case, &mut counter);
+        assert_eq!(counter.elements, 2);
+        assert_eq!(counter.directives, 1);
+        let mut counter = Counter {
+            stop: Some("p"),
+            ..Default::default()
+        };
+        count(case, &mut counter);
+        assert_eq!(counter.elements, 2);
+        assert_eq!(counter.directives, 1);
+    }
+
+    #[test]
+    fn test_marker_comment() {
+        let case = "<!-- @vue-ignore --> <p :a='b'/><div/><!--other--><span/>";
+        let children = base_parse(case).children;
+        let comment = children[0].get_comment().unwrap();
+        assert_eq!(comment.source, " @vue-ignore ");
+        assert!(comment.is_marker("@vue-ignore"));
+        let marked: Vec<_> = marked_elements(&children, "@vue-ignore")
+            .map(|e| e.tag_name)
+            .collect();
+        assert_eq!(marked, ["p"]);
+    }
+
+    #[test]
+    fn test_condense_whitespace() {
+        let parse = |s, whitespace| {
+            let option = ParseOption {
+                whitespace,
+                ..Default::default()
+            };
+            let eh = std::rc::Rc::new(TestErrorHandler);
+            Parser::new(option).parse(base_scan(s), eh)
+        };
+        let children = |s, whitespace| {
+            let mut ast = parse(s, whitespace);
+            ast.children.remove(0).into_element().children
+        };
+        let is_text = |n: &AstNode| matches!(n, AstNode::Text(_));
+        // leading and trailing whitespace inside a block is removed
+        let c = children(
+            "<div v-if='a'>\n  <p/> <p/>\n</div>",
+            WhitespaceStrategy::Condense,
+        );
+        assert_eq!(c.len(), 3);
+        assert!(is_element(&c[0]) && is_text(&c[1]) && is_element(&c[2]));
+        let c = children(
+            "<div>\n  <!--c--> <p/>  <!--d-->\n</div>",
+            WhitespaceStrategy::Condense,
+        );
+        assert_eq!(c.len(), 3);
+        assert!(!c.iter().any(is_text));
+        // whitespace as the only child does not produce a text child
+        let c = children("<div>  </div>", WhitespaceStrategy::Preserve);
+        assert!(c.is_empty());
+        let c = children(
+            "<div>\n  <!--c--> <p/>\n</div>",
+            WhitespaceStrategy::Preserve,
+        );
+        assert_eq!(c.len(), 3);
+        assert!(is_text(&c[1]));
+    }
+
     pub fn base_parse(s: &str) -> AstRoot {
         let tokens = base_scan(s);
         let parser = Parser::new(ParseOption {
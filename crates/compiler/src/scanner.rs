@@ -7,8 +7,8 @@ use super::{
     util::{non_whitespace, VStr},
     Name, Position, SourceLocation,
 };
-use rustc_hash::FxHashSet;
-use std::{iter::FusedIterator, str::Bytes};
+use alloc::{collections::BTreeSet, string::String, vec, vec::Vec};
+use core::{iter::FusedIterator, str::Bytes};
 
 #[cfg(feature = "serde")]
 use serde::Serialize;
@@ -69,8 +69,21 @@ impl<'a> From<&'a str> for Token<'a> {
 pub struct ScanOption {
     pub delimiters: (String, String),
     pub get_text_mode: fn(&str) -> TextMode,
+    /// Extra tags whose content is taken verbatim like script/style.
+    pub raw_elements: BTreeSet<String>,
 }
 
+impl ScanOption {
+    pub fn text_mode(&self, tag: &str) -> TextMode {
+        if self.raw_elements.contains(tag) {
+            TextMode::RawText
+        } else {
+            (self.get_text_mode)(tag)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
 impl Default for ScanOption {
     fn default() -> Self {
         use crate::compiler::CompileOption;
@@ -78,6 +91,17 @@ impl Default for ScanOption {
     }
 }
 
+#[cfg(not(feature = "std"))]
+impl Default for ScanOption {
+    fn default() -> Self {
+        Self {
+            delimiters: ("{{".into(), "}}".into()),
+            get_text_mode: |_| TextMode::Data,
+            raw_elements: BTreeSet::new(),
+        }
+    }
+}
+
 /// A scanner needs to implement this trait to know if it is_in_html_namespace.
 /// A parser tells scanner the current namespace through the trait's method.
 // Because parsing CDATA requires scanner to know the parser's state.
@@ -141,7 +165,7 @@ impl Scanner {
         }
     }
     pub fn scan<'a>(&self, source: &'a str, err_handle: RcErrHandle) -> Tokens<'a> {
-        Tokens {
+        let mut tokens = Tokens {
             source,
             err_handle,
             position: Default::default(),
@@ -151,7 +175,9 @@ impl Scanner {
             last_start_tag_name: None,
             is_in_html_namespace: true,
             delimiter_first_char: self.delimiter_first_char,
-        }
+        };
+        tokens.skip_leading_declarations();
+        tokens
     }
 }
 
@@ -258,8 +284,7 @@ impl<'a> Tokens<'a> {
         let tag = self.scan_tag_name();
         // https://html.spec.whatwg.org/multipage/parsing.html#parsing-elements-that-contain-only-text
         // Parsing algorithms are always invoked in response to a start tag token.
-        let parsing_algorithm = self.option.get_text_mode;
-        self.mode = parsing_algorithm(tag.name);
+        self.mode = self.option.text_mode(tag.name);
         if self.mode != TextMode::Data {
             self.last_start_tag_name.replace(tag.name);
         }
@@ -287,8 +312,7 @@ impl<'a> Tokens<'a> {
     // return attributes and if the tag is self closing
     // https://html.spec.whatwg.org/multipage/parsing.html#before-attribute-name-state
     fn scan_attributes(&mut self) -> Vec<Attribute<'a>> {
-        let mut attrs = vec![]; // TODO: size hint?
-        let mut set = FxHashSet::default();
+        let mut attrs: Vec<Attribute<'a>> = vec![]; // TODO: size hint?
         loop {
             // TODO: forbid infinite loop
             self.skip_whitespace();
@@ -299,13 +323,13 @@ impl<'a> Tokens<'a> {
                 continue;
             }
             let attr = self.scan_attribute();
-            if set.contains(attr.name) {
+            // linear scan is fast enough since a tag rarely has many attributes
+            if attrs.iter().any(|a| a.name == attr.name) {
                 // new attribute must be removed from the token.
                 // NB: original vue compiler does not remove it.
                 self.emit_error(ErrorKind::DuplicateAttribute);
                 continue;
             }
-            set.insert(attr.name);
             attrs.push(attr);
         }
     }
@@ -660,9 +684,11 @@ impl<'a> Tokens<'a> {
                 break;
             }
             // https://html.spec.whatwg.org/multipage/parsing.html#rawtext-end-tag-name-state
-            let is_appropriate_end = source[i + 2..e].eq_ignore_ascii_case(tag_name);
+            // compare bytes since e may not be on a char boundary
+            let bytes = source.as_bytes();
+            let is_appropriate_end = bytes[i + 2..e].eq_ignore_ascii_case(tag_name.as_bytes());
             // equivalent to source[e..] does not start with valid_name_char
-            let terminated = !is_valid_name_char(source.as_bytes()[e]);
+            let terminated = !is_valid_name_char(bytes[e]);
             if is_appropriate_end && terminated {
                 // found!
                 return i;
@@ -716,6 +742,31 @@ impl<'a> Tokens<'a> {
         ret
     }
 
+    // Users may paste a full HTML document starting with <!DOCTYPE html>
+    // or <?xml ...?>. They mean nothing to Vue so we drop them with a warning.
+    fn skip_leading_declarations(&mut self) {
+        let mut skipped = false;
+        loop {
+            let s = self.source.trim_start_matches(|c| !non_whitespace(c));
+            let is_declaration = s.starts_with("<?")
+                || (s.starts_with("<!") && !s.starts_with("<!--") && !s.starts_with("<![CDATA["));
+            if !is_declaration {
+                break;
+            }
+            self.skip_whitespace();
+            let start = self.current_position();
+            let end = self.source.find('>').map_or(self.source.len(), |i| i + 1);
+            self.move_by(end);
+            let loc = self.get_location_from(start);
+            let err = CompilationError::new(ErrorKind::UnexpectedDoctype).with_location(loc);
+            self.err_handle.on_error(err);
+            skipped = true;
+        }
+        if skipped {
+            self.skip_whitespace();
+        }
+    }
+
     fn skip_whitespace(&mut self) -> usize {
         let idx = self.source.find(non_whitespace);
         let len = idx.unwrap_or(self.source.len());
@@ -835,6 +886,16 @@ pub mod test {
         assert_eq!(val.content.into_string(), "&amp;");
     }
 
+    #[test]
+    fn test_attr_value_quoting() {
+        for case in ["<p id=foo>", "<p id='foo'>", r#"<p id="foo">"#] {
+            let mut a: Vec<_> = base_scan(case).collect();
+            let tag = cast!(a.remove(0), Token::StartTag);
+            let val = tag.attributes[0].value.as_ref().unwrap();
+            assert_eq!(val.content.raw, "foo", "{}", case);
+        }
+    }
+
     #[test]
     fn test_simple_text_with_invalid_end_tag() {
         let a: Vec<_> = base_scan("some text</div>").collect();
@@ -0,0 +1,143 @@
+//! Test support for comparing IR trees, enabled by the `testing` feature.
+//! IR is compared by its serde serialization so any ConvertInfo works.
+//! Source locations are ignored since fixtures are usually built by hand.
+
+use crate::converter::{BaseConvertInfo, BaseIR, BaseRoot};
+use crate::ir::{IRNode, IRRoot, TextIR, VNodeIR};
+use crate::Js;
+use serde::Serialize;
+use serde_json::Value;
+use smallvec::smallvec;
+
+/// Asserts two IR values serialize to the same tree.
+/// Panics with a line diff of the pretty printed JSON otherwise.
+pub fn assert_ir_eq<A: Serialize, E: Serialize>(actual: &A, expected: &E) {
+    let actual = ir_to_string(actual);
+    let expected = ir_to_string(expected);
+    if actual != expected {
+        panic!(
+            "IR mismatch (-expected +actual):\n{}",
+            diff_lines(&expected, &actual)
+        );
+    }
+}
+
+/// Pretty prints IR as JSON with source locations removed.
+pub fn ir_to_string<T: Serialize>(ir: &T) -> String {
+    let mut value = serde_json::to_value(ir).expect("IR should be serializable");
+    strip_locations(&mut value);
+    serde_json::to_string_pretty(&value).expect("JSON value should be printable")
+}
+
+fn strip_locations(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.remove("loc");
+            map.remove("locs");
+            map.values_mut().for_each(strip_locations);
+        }
+        Value::Array(arr) => arr.iter_mut().for_each(strip_locations),
+        _ => (),
+    }
+}
+
+// a plain LCS line diff. IR fixtures are small so O(n*m) is fine.
+fn diff_lines(expected: &str, actual: &str) -> String {
+    let old: Vec<_> = expected.lines().collect();
+    let new: Vec<_> = actual.lines().collect();
+    let (n, m) = (old.len(), new.len());
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            out.push_str("  ");
+            out.push_str(old[i]);
+            i += 1;
+            j += 1;
+        } else if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            out.push_str("+ ");
+            out.push_str(new[j]);
+            j += 1;
+        } else {
+            out.push_str("- ");
+            out.push_str(old[i]);
+            i += 1;
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Builds a root fixture with default top scope.
+pub fn root(body: Vec<BaseIR<'_>>) -> BaseRoot<'_> {
+    IRRoot {
+        body,
+        top_scope: Default::default(),
+    }
+}
+
+/// Builds a static text fixture as converted from template text.
+pub fn text(s: &str) -> BaseIR<'_> {
+    IRNode::TextCall(TextIR {
+        fast_path: false,
+        need_patch: false,
+        texts: smallvec![Js::str_lit(s)],
+        locs: smallvec![Default::default()],
+    })
+}
+
+/// Builds a plain element fixture. Tweak other fields with `vnode_mut`.
+pub fn element<'a>(tag: &'a str, children: Vec<BaseIR<'a>>) -> BaseIR<'a> {
+    IRNode::VNodeCall(VNodeIR {
+        tag: Js::str_lit(tag),
+        children,
+        ..Default::default()
+    })
+}
+
+/// Returns the VNodeIR inside a fixture node for further tweaking.
+pub fn vnode_mut<'a, 'b>(node: &'b mut BaseIR<'a>) -> &'b mut VNodeIR<BaseConvertInfo<'a>> {
+    match node {
+        IRNode::VNodeCall(vn) => vn,
+        _ => panic!("fixture is not a VNodeCall"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::converter::test::base_convert;
+
+    #[test]
+    fn test_converted_template_matches_fixture() {
+        let ir = base_convert("<div>hello<p/></div>");
+        let expected = root(vec![element(
+            "div",
+            vec![text("hello"), element("p", vec![])],
+        )]);
+        assert_ir_eq(&ir, &expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "IR mismatch")]
+    fn test_mismatch_panics() {
+        let ir = base_convert("<div/>");
+        assert_ir_eq(&ir, &root(vec![element("span", vec![])]));
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        let diff = diff_lines("a\nb\nc", "a\nx\nc");
+        assert_eq!(diff, "  a\n+ x\n- b\n  c\n");
+    }
+}
@@ -0,0 +1,147 @@
+//! Flat token stream with byte spans for editor tooling.
+//! Unlike Scanner, it yields tag names and attributes as separate tokens
+//! so a language server can tell where the cursor is without building AST.
+
+use super::{
+    error::NoopErrorHandler,
+    scanner::{Attribute, ScanOption, Scanner, Token as ScanToken, Tokens, Locatable},
+};
+use alloc::{collections::VecDeque, rc::Rc};
+use core::ops::Range;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenKind {
+    /// tag name in start tag, e.g. `div` in `<div>`
+    TagOpen,
+    /// e.g. `:id` in `<div :id="x">`
+    AttrName,
+    /// attribute value without quotes, e.g. `x` in `<div :id="x">`
+    AttrValue,
+    /// the whole end tag, e.g. `</div>`
+    TagClose,
+    Text,
+    /// interpolation with delimiters, e.g. `{{ y }}`
+    Interpolation,
+    /// comment with `<!--` and `-->`
+    Comment,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    /// byte offsets in the source
+    pub span: Range<usize>,
+}
+
+pub struct Tokenizer<'a> {
+    source: &'a str,
+    tokens: Tokens<'a>,
+    pending: VecDeque<Token>,
+}
+
+impl<'a> Tokenizer<'a> {
+    pub fn new(source: &'a str, option: ScanOption) -> Self {
+        let tokens = Scanner::new(option).scan(source, Rc::new(NoopErrorHandler));
+        Self {
+            source,
+            tokens,
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn push(&mut self, kind: TokenKind, span: Range<usize>) {
+        self.pending.push_back(Token { kind, span });
+    }
+
+    fn push_attr(&mut self, attr: &Attribute) {
+        let name = &attr.name_loc;
+        self.push(TokenKind::AttrName, name.start.offset..name.end.offset);
+        if let Some(val) = &attr.value {
+            let loc = &val.location;
+            let mut start = loc.start.offset;
+            if self.source[start..].starts_with(&['"', '\''][..]) {
+                start += 1;
+            }
+            let end = start + val.content.raw.len();
+            self.push(TokenKind::AttrValue, start..end);
+        }
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Token;
+    fn next(&mut self) -> Option<Token> {
+        if let Some(t) = self.pending.pop_front() {
+            return Some(t);
+        }
+        let token = self.tokens.next()?;
+        let start = self.tokens.last_position().offset;
+        let end = self.tokens.current_position().offset;
+        match token {
+            ScanToken::StartTag(tag) => {
+                // skip the leading `<`
+                let name_start = start + 1;
+                let name_end = name_start + tag.name.len();
+                self.push(TokenKind::TagOpen, name_start..name_end);
+                for attr in &tag.attributes {
+                    self.push_attr(attr);
+                }
+            }
+            ScanToken::EndTag(_) => self.push(TokenKind::TagClose, start..end),
+            ScanToken::Text(_) => self.push(TokenKind::Text, start..end),
+            ScanToken::Interpolation(_) => self.push(TokenKind::Interpolation, start..end),
+            ScanToken::Comment(_) => self.push(TokenKind::Comment, start..end),
+        }
+        self.pending.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+
+    fn tokenize(s: &str) -> Vec<(TokenKind, &str)> {
+        Tokenizer::new(s, ScanOption::default())
+            .map(|t| (t.kind, &s[t.span]))
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        use TokenKind::*;
+        let s = r#"<div :id="x">{{ y }}</div>"#;
+        let spans: Vec<_> = Tokenizer::new(s, ScanOption::default())
+            .map(|t| t.span)
+            .collect();
+        assert_eq!(spans, [1..4, 5..8, 10..11, 13..20, 20..26]);
+        assert_eq!(
+            tokenize(s),
+            [
+                (TagOpen, "div"),
+                (AttrName, ":id"),
+                (AttrValue, "x"),
+                (Interpolation, "{{ y }}"),
+                (TagClose, "</div>"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_unquoted_and_text() {
+        use TokenKind::*;
+        let tokens = tokenize("<p a=b c>t<!--d--></p>");
+        assert_eq!(
+            tokens,
+            [
+                (TagOpen, "p"),
+                (AttrName, "a"),
+                (AttrValue, "b"),
+                (AttrName, "c"),
+                (Text, "t"),
+                (Comment, "<!--d-->"),
+                (TagClose, "</p>"),
+            ]
+        );
+    }
+}
@@ -1,10 +1,12 @@
 // this module collects following entities:
 // runtime helpers
 // component/directive asset
+// cache slots and patched nodes for stats
 use super::{
     BaseFor, BaseIf, BaseInfo, BaseRenderSlot, BaseText, BaseVNode, BaseVSlot, BaseCache, CorePass,
 };
 use crate::converter::BaseRoot;
+use crate::error::{CompilationError, CompilationErrorKind as ErrorKind, RcErrHandle};
 use crate::flags::{HelperCollector, RuntimeHelper as RH};
 use crate::ir::{IRNode as IR, JsExpr as Js};
 use crate::util::{get_vnode_call_helper, VStr};
@@ -13,26 +15,74 @@ use std::mem::swap;
 
 #[derive(Default)]
 pub struct EntityCollector<'a> {
+    force_fragment_root: bool,
+    /// used helpers in the list are reported as ForbiddenHelper
+    deny_list: Option<(HelperCollector, RcErrHandle)>,
+    helper_strs: &'static [&'static str],
     helpers: HelperCollector,
     components: FxHashSet<VStr<'a>>,
     directives: FxHashSet<VStr<'a>>,
+    cache_slots: usize,
+    patched_nodes: usize,
+}
+
+impl<'a> EntityCollector<'a> {
+    pub fn new(force_fragment_root: bool) -> Self {
+        Self {
+            force_fragment_root,
+            ..Default::default()
+        }
+    }
+    #[must_use]
+    pub fn with_deny_list(
+        mut self,
+        deny: HelperCollector,
+        helper_strs: &'static [&'static str],
+        err_handle: RcErrHandle,
+    ) -> Self {
+        if !deny.is_empty() {
+            self.deny_list = Some((deny, err_handle));
+            self.helper_strs = helper_strs;
+        }
+        self
+    }
+    fn report_denied(&self) {
+        let (deny, err_handle) = match &self.deny_list {
+            Some(d) => d,
+            None => return,
+        };
+        for rh in self.helpers.intersect(deny) {
+            let name = rh.helper_str(self.helper_strs);
+            let error =
+                CompilationError::new(ErrorKind::ForbiddenHelper).with_additional_message(name);
+            err_handle.on_error(error);
+        }
+    }
 }
 
 impl<'a> CorePass<BaseInfo<'a>> for EntityCollector<'a> {
     fn exit_root(&mut self, r: &mut BaseRoot<'a>) {
-        if r.body.len() > 1 {
+        if r.body.len() > 1 || (self.force_fragment_root && !r.body.is_empty()) {
+            // codegen wraps multiple roots in a synthetic fragment vnode
             self.helpers.collect(RH::FRAGMENT);
+            self.helpers.collect(RH::CREATE_ELEMENT_VNODE);
         }
+        self.report_denied();
         let scope = &mut r.top_scope;
         swap(&mut scope.helpers, &mut self.helpers);
         swap(&mut scope.components, &mut self.components);
         swap(&mut scope.directives, &mut self.directives);
+        scope.cache_slots = self.cache_slots;
+        scope.patched_nodes = self.patched_nodes;
     }
     fn exit_js_expr(&mut self, e: &mut Js) {
         match e {
             Js::Call(h, ..) | Js::Symbol(h) => {
                 self.helpers.collect(*h);
             }
+            Js::FuncSimple { cache: true, .. } | Js::FuncCompound { cache: true, .. } => {
+                self.cache_slots += 1;
+            }
             _ => {}
         }
     }
@@ -50,6 +100,7 @@ impl<'a> CorePass<BaseInfo<'a>> for EntityCollector<'a> {
         self.helpers.collect(RH::CREATE_ELEMENT_BLOCK);
         self.helpers.collect(RH::RENDER_LIST);
         self.helpers.collect(RH::FRAGMENT);
+        self.patched_nodes += 1;
     }
     fn exit_vnode(&mut self, v: &mut BaseVNode<'a>) {
         if !v.directives.is_empty() {
@@ -64,6 +115,9 @@ impl<'a> CorePass<BaseInfo<'a>> for EntityCollector<'a> {
         if v.is_block {
             self.helpers.collect(RH::OPEN_BLOCK);
         }
+        if !v.patch_flag.is_empty() {
+            self.patched_nodes += 1;
+        }
         let h = get_vnode_call_helper(v);
         self.helpers.collect(h);
         if !v.is_component {
@@ -105,17 +159,23 @@ impl<'a> CorePass<BaseInfo<'a>> for EntityCollector<'a> {
     fn exit_text(&mut self, t: &mut BaseText<'a>) {
         if !t.fast_path {
             self.helpers.collect(RH::CREATE_TEXT);
+            if t.need_patch {
+                self.patched_nodes += 1;
+            }
         }
     }
-    fn enter_cache(&mut self, r: &mut BaseCache<'a>) {
+    fn exit_cache(&mut self, r: &mut BaseCache<'a>) {
         use crate::ir::CacheKind::{Once, Memo, MemoInVFor};
         match r.kind {
+            // static v-once is hoisted instead
+            Once if matches!(*r.child, IR::Hoisted(_)) => return,
             Once => self.helpers.collect(RH::SET_BLOCK_TRACKING),
             Memo(_) => self.helpers.collect(RH::WITH_MEMO),
             MemoInVFor { .. } => {
                 self.helpers.collect(RH::IS_MEMO_SAME);
             }
         }
+        self.cache_slots += 1;
     }
 }
 
@@ -165,4 +225,20 @@ mod test {
         assert!(helpers.contains(RH::RENDER_LIST));
         assert!(helpers.contains(RH::WITH_CTX));
     }
+    #[test]
+    fn test_deny_helpers() {
+        use crate::error::VecErrorHandler;
+        use std::rc::Rc;
+        let eh = Rc::new(VecErrorHandler::default());
+        let deny = [RH::RENDER_LIST, RH::SUSPENSE].iter().copied().collect();
+        let collector = EntityCollector::default().with_deny_list(deny, &[], eh.clone());
+        let mut ir = base_convert("<p v-for='a in b'/>");
+        BaseTransformer::transform(&mut ir, collector);
+        // denied helpers are still collected for codegen
+        assert!(ir.top_scope.helpers.contains(RH::RENDER_LIST));
+        let errors = eh.errors();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0].kind, ErrorKind::ForbiddenHelper));
+        assert_eq!(errors[0].additional_message, Some("renderList"));
+    }
 }
@@ -1,13 +1,82 @@
 /// hoist static element like `<div class="static">static text</div>`
 /// to a top level const. This improves runtime performance by reducing dom diffing.
-use super::{BaseInfo, BaseVNode, BaseRoot, CorePass, Js, BaseText};
+use super::{BaseInfo, BaseVNode, BaseRoot, BaseCache, CorePass, Js, BaseText};
 use crate::converter::{BaseIR, Hoist};
-use crate::ir::IRNode;
+use crate::ir::{IRNode, CacheKind};
 use crate::flags::{StaticLevel, PatchFlag};
+use rustc_hash::FxHashMap;
+use std::{
+    cell::RefCell,
+    hash::{Hash, Hasher},
+    rc::Rc,
+};
 
 #[derive(Default)]
 pub struct HoistStatic<'a> {
     hoists: Vec<Hoist<'a>>,
+    sink: Option<Rc<HoistSink>>,
+}
+
+/// Shares hoists among templates compiled together by `compile_many`.
+/// Identical hoists are keyed by what codegen writes for them and only the
+/// first template declares them. Later templates reference the same variable.
+#[derive(Default)]
+pub struct HoistSink {
+    /// hoist prefix of the template being compiled, None outside a batch
+    prefix: RefCell<Option<String>>,
+    names: RefCell<FxHashMap<Vec<u8>, String>>,
+}
+
+impl HoistSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Forget hoists of the previous batch.
+    pub fn clear(&self) {
+        self.names.borrow_mut().clear();
+        self.prefix.borrow_mut().take();
+    }
+    /// Hoists of following transforms are named with the prefix.
+    pub fn start_template(&self, prefix: &str) {
+        *self.prefix.borrow_mut() = Some(prefix.into());
+    }
+    /// Stop sharing so single template compiles keep their own hoists.
+    pub fn end_batch(&self) {
+        self.prefix.borrow_mut().take();
+    }
+    fn prefix(&self) -> Option<String> {
+        self.prefix.borrow().clone()
+    }
+    /// Returns the existing name of the key or registers the new one.
+    fn share(&self, key: HoistKey, name: String) -> Option<String> {
+        let mut names = self.names.borrow_mut();
+        if let Some(existing) = names.get(&key.0) {
+            return Some(existing.clone());
+        }
+        names.insert(key.0, name);
+        None
+    }
+}
+
+/// Bytes fed by Hash impls. Compared in full so no two hoists share by collision.
+#[derive(Default)]
+struct HoistKey(Vec<u8>);
+impl Hasher for HoistKey {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+    fn finish(&self) -> u64 {
+        unreachable!("HoistKey is compared by bytes")
+    }
+}
+
+impl<'a> HoistStatic<'a> {
+    pub fn new(sink: Option<Rc<HoistSink>>) -> Self {
+        Self {
+            hoists: vec![],
+            sink,
+        }
+    }
 }
 
 impl<'a> CorePass<BaseInfo<'a>> for HoistStatic<'a> {
@@ -18,6 +87,20 @@ impl<'a> CorePass<BaseInfo<'a>> for HoistStatic<'a> {
         self.walk_children(&mut r.body, bail_out_hoist);
         std::mem::swap(&mut r.top_scope.hoists, &mut self.hoists);
     }
+    // Hoisting is cheaper than caching. A fully static v-once element is
+    // hoisted here so no cache slot or block tracking is needed for it.
+    fn exit_cache(&mut self, c: &mut BaseCache<'a>) {
+        if !matches!(c.kind, CacheKind::Once) {
+            return;
+        }
+        if let Some(e) = extract_plain_element(&mut c.child) {
+            if get_vnode_static_level(e) >= StaticLevel::CanHoist {
+                e.patch_flag = PatchFlag::HOISTED;
+                let e = Hoist::FullElement(std::mem::take(e));
+                *c.child = IRNode::Hoisted(self.hoist(e));
+            }
+        }
+    }
 }
 
 fn is_plain_element(node: &BaseVNode) -> bool {
@@ -132,9 +215,90 @@ impl<'a> HoistStatic<'a> {
 
     fn hoist(&mut self, expr: Hoist<'a>) -> usize {
         let len = self.hoists.len();
+        let expr = self.share_hoist(expr, len);
         self.hoists.push(expr);
         len
     }
+
+    fn share_hoist(&self, expr: Hoist<'a>, index: usize) -> Hoist<'a> {
+        let sink = match &self.sink {
+            Some(sink) => sink,
+            None => return expr,
+        };
+        let prefix = match sink.prefix() {
+            Some(prefix) => prefix,
+            None => return expr,
+        };
+        let mut key = HoistKey::default();
+        if self.hash_hoist(&expr, &prefix, &mut key).is_none() {
+            return expr;
+        }
+        let name = format!("{}{}", prefix, index);
+        match sink.share(key, name) {
+            Some(existing) => Hoist::Shared(existing),
+            None => expr,
+        }
+    }
+
+    // Hashes what codegen writes. None means the hoist cannot be shared.
+    fn hash_hoist(&self, hoist: &Hoist, prefix: &str, h: &mut HoistKey) -> Option<()> {
+        std::mem::discriminant(hoist).hash(h);
+        match hoist {
+            Hoist::FullElement(e) => self.hash_vnode(e, prefix, h)?,
+            Hoist::StaticProps(p) => format!("{:?}", p).hash(h),
+            Hoist::ChildrenArray(children) => {
+                for child in children {
+                    self.hash_node(child, prefix, h)?;
+                }
+            }
+            Hoist::DynamicPropsHint(d) => {
+                let mut props: Vec<_> = d.iter().map(|v| v.into_string()).collect();
+                props.sort_unstable();
+                props.hash(h);
+            }
+            Hoist::Shared(_) => return None,
+        }
+        Some(())
+    }
+
+    fn hash_node(&self, node: &BaseIR, prefix: &str, h: &mut HoistKey) -> Option<()> {
+        std::mem::discriminant(node).hash(h);
+        match node {
+            IRNode::VNodeCall(e) => self.hash_vnode(e, prefix, h)?,
+            IRNode::TextCall(t) => {
+                (t.fast_path, t.need_patch).hash(h);
+                for text in t.texts.iter() {
+                    format!("{:?}", text).hash(h);
+                }
+            }
+            IRNode::CommentCall(c) => c.hash(h),
+            // nested hoists are equal if they refer to the same variable
+            IRNode::Hoisted(i) => match &self.hoists[*i] {
+                Hoist::Shared(name) => name.hash(h),
+                _ => format!("{}{}", prefix, i).hash(h),
+            },
+            _ => return None,
+        }
+        Some(())
+    }
+
+    fn hash_vnode(&self, e: &BaseVNode, prefix: &str, h: &mut HoistKey) -> Option<()> {
+        let has_hoisted = e.hoisted.has_props_hoisted().is_some()
+            || e.hoisted.has_children_hoisted().is_some()
+            || e.hoisted.has_dynamic_props_hoisted().is_some();
+        if has_hoisted || !e.directives.is_empty() || !e.dynamic_props.is_empty() {
+            return None;
+        }
+        format!("{:?}", e.tag).hash(h);
+        e.props.as_ref().map(|p| format!("{:?}", p)).hash(h);
+        e.patch_flag.bits().hash(h);
+        (e.is_block, e.disable_tracking, e.is_component).hash(h);
+        e.children.len().hash(h);
+        for child in &e.children {
+            self.hash_node(child, prefix, h)?;
+        }
+        Some(())
+    }
 }
 
 fn is_single_element_root(r: &BaseRoot) -> bool {
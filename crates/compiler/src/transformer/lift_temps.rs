@@ -0,0 +1,181 @@
+// lift repeated constant literal prop values into render function locals.
+// e.g. two `:style="{color: 'red'}"` share one `let _temp0 = {color: 'red'}`.
+// Only side-effect free literals are lifted since the temp is evaluated once.
+// Sharing one object across vnodes is fine as hoisted props are shared too.
+// Only unconditionally evaluated props are lifted: expressions in
+// v-if/v-for/slot/v-once/v-memo may be unsafe or stale to evaluate eagerly.
+// it must run after HoistStatic so static props are already hoisted.
+use super::{BaseInfo, BaseRoot, BaseVNode, CorePass};
+use crate::converter::BaseIR;
+use crate::ir::{IRNode as IR, JsExpr as Js};
+use crate::util::rslint::is_constant_collection;
+
+pub struct TempLifter;
+
+impl<'a> CorePass<BaseInfo<'a>> for TempLifter {
+    fn exit_root(&mut self, r: &mut BaseRoot<'a>) {
+        let mut candidates = vec![];
+        for child in r.body.iter_mut() {
+            walk_ir(child, &mut |e| count_candidate(&mut candidates, e));
+        }
+        let temps: Vec<_> = candidates
+            .into_iter()
+            .filter(|(_, count)| *count > 1)
+            .map(|(e, _)| e)
+            .collect();
+        if temps.is_empty() {
+            return;
+        }
+        for child in r.body.iter_mut() {
+            walk_ir(child, &mut |e| replace_with_temp(&temps, e));
+        }
+        r.top_scope.temps = temps;
+    }
+}
+
+fn count_candidate<'a>(candidates: &mut Vec<(Js<'a>, usize)>, e: &mut Js<'a>) {
+    if !is_constant_literal(e) {
+        return;
+    }
+    if let Some(c) = candidates.iter_mut().find(|c| same_expr(&c.0, e)) {
+        c.1 += 1;
+    } else {
+        candidates.push((e.clone(), 1));
+    }
+}
+
+fn replace_with_temp<'a>(temps: &[Js<'a>], e: &mut Js<'a>) {
+    if let Some(i) = temps.iter().position(|t| same_expr(t, e)) {
+        *e = Js::Compound(vec![Js::Src("_temp"), Js::Num(i)]);
+    }
+}
+
+fn walk_ir<'a, F>(ir: &mut BaseIR<'a>, f: &mut F)
+where
+    F: FnMut(&mut Js<'a>),
+{
+    // v-if/v-for/slot/cache are not evaluated unconditionally
+    if let IR::VNodeCall(vn) = ir {
+        walk_vnode(vn, f);
+    }
+}
+
+fn walk_vnode<'a, F>(vn: &mut BaseVNode<'a>, f: &mut F)
+where
+    F: FnMut(&mut Js<'a>),
+{
+    match &mut vn.props {
+        Some(Js::Props(ps)) => ps.iter_mut().for_each(|p| f(&mut p.1)),
+        Some(Js::Call(_, args)) => {
+            for arg in args.iter_mut() {
+                if let Js::Props(ps) = arg {
+                    ps.iter_mut().for_each(|p| f(&mut p.1));
+                }
+            }
+        }
+        _ => (),
+    }
+    for child in vn.children.iter_mut() {
+        walk_ir(child, f);
+    }
+}
+
+// plain strings and numbers are not worth a temp
+fn is_constant_literal(e: &Js) -> bool {
+    match e {
+        Js::Simple(v, _) => v.ops.is_empty() && is_constant_collection(v.raw),
+        Js::Props(ps) => !ps.is_empty() && ps.iter().all(|(k, v)| is_constant(k) && is_constant(v)),
+        Js::Array(es) => !es.is_empty() && es.iter().all(is_constant),
+        _ => false,
+    }
+}
+
+fn is_constant(e: &Js) -> bool {
+    matches!(e, Js::StrLit(_) | Js::Num(_)) || is_constant_literal(e)
+}
+
+fn same_expr(a: &Js, b: &Js) -> bool {
+    let same_list = |a: &[Js], b: &[Js]| {
+        a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| same_expr(a, b))
+    };
+    match (a, b) {
+        (Js::Src(a), Js::Src(b)) | (Js::Param(a), Js::Param(b)) => a == b,
+        (Js::Num(a), Js::Num(b)) => a == b,
+        (Js::StrLit(a), Js::StrLit(b)) => a == b,
+        (Js::Simple(a, _), Js::Simple(b, _)) => a == b,
+        (Js::Symbol(a), Js::Symbol(b)) => a == b,
+        (Js::Compound(a), Js::Compound(b)) | (Js::Array(a), Js::Array(b)) => same_list(a, b),
+        (Js::Call(h1, a), Js::Call(h2, b)) => h1 == h2 && same_list(a, b),
+        (Js::Props(a), Js::Props(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b.iter())
+                    .all(|(a, b)| same_expr(&a.0, &b.0) && same_expr(&a.1, &b.1))
+        }
+        // handlers may be cached, never share them
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::test::base_convert;
+    use super::*;
+    use crate::transformer::{BaseTransformer, Transformer};
+
+    fn transform(s: &str) -> BaseRoot<'_> {
+        let mut ir = base_convert(s);
+        BaseTransformer::transform(&mut ir, TempLifter);
+        ir
+    }
+
+    fn get_style<'a, 'b>(ir: &'b BaseIR<'a>) -> &'b Js<'a> {
+        let vn = match ir {
+            IR::VNodeCall(vn) => vn,
+            _ => panic!("should be vnode"),
+        };
+        match &vn.props {
+            Some(Js::Props(ps)) => &ps[0].1,
+            _ => panic!("should have props"),
+        }
+    }
+
+    #[test]
+    fn test_lift_object_literal() {
+        let ir = transform("<p :style=\"{color: 'red'}\"/><span :style=\"{color: 'red'}\"/>");
+        assert_eq!(ir.top_scope.temps.len(), 1);
+        for child in ir.body.iter() {
+            match get_style(child) {
+                Js::Compound(v) => assert!(matches!(v[..], [Js::Src("_temp"), Js::Num(0)])),
+                _ => panic!("style should be replaced by temp"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_not_lift_trivial() {
+        let ir = transform("<p :style='a'/><span :style='a'/>");
+        assert!(ir.top_scope.temps.is_empty());
+        let ir = transform("<p :style=\"'a'\"/><span :style=\"'a'\"/>");
+        assert!(ir.top_scope.temps.is_empty());
+    }
+
+    #[test]
+    fn test_not_lift_impure() {
+        // each site must evaluate its own expression
+        let ir = transform("<p :a='rand()' :b='rand()'/>");
+        assert!(ir.top_scope.temps.is_empty());
+        let ir = transform("<p :a='count++'/><i :a='count++'/>");
+        assert!(ir.top_scope.temps.is_empty());
+        let ir = transform("<p :style='{color: c}'/><span :style='{color: c}'/>");
+        assert!(ir.top_scope.temps.is_empty());
+    }
+
+    #[test]
+    fn test_not_lift_guarded() {
+        let ir = transform("<p :style='[1]'/><span v-if='a' :style='[1]'/>");
+        assert!(ir.top_scope.temps.is_empty());
+        let ir = transform("<p :style='[1]'/><i v-for='c in d' :style='[1]'/>");
+        assert!(ir.top_scope.temps.is_empty());
+    }
+}
@@ -4,9 +4,18 @@ use super::{BaseFor, BaseIf, BaseInfo, BaseText, BaseVNode, CorePass};
 use crate::converter::BaseIR;
 use crate::flags::{PatchFlag, RuntimeHelper as RH, StaticLevel};
 use crate::ir::{IRNode as IR, JsExpr as Js, Prop};
-use crate::util::is_builtin_symbol;
+use crate::util::{is_builtin_symbol, is_reserved_prop, VStr};
 
-pub struct PatchFlagMarker;
+pub struct PatchFlagMarker {
+    // whether default keys are injected to v-if branch children
+    inject_key: bool,
+}
+
+impl PatchFlagMarker {
+    pub fn new(inject_key: bool) -> Self {
+        Self { inject_key }
+    }
+}
 
 impl<'a> CorePass<BaseInfo<'a>> for PatchFlagMarker {
     fn enter_if(&mut self, i: &mut BaseIf<'a>) {
@@ -17,15 +26,21 @@ impl<'a> CorePass<BaseInfo<'a>> for PatchFlagMarker {
                     vn.is_block = true;
                 }
             }
+            if !self.inject_key {
+                continue;
+            }
             let props = match &mut *branch.child {
                 IR::VNodeCall(v) => &mut v.props,
                 IR::RenderSlotCall(r) => &mut r.slot_props,
-                IR::For(f) => return f.key = Some(Js::Num(branch.info)),
-                _ => return,
+                IR::For(f) => {
+                    f.key = Some(Js::Num(branch.info));
+                    continue;
+                }
+                _ => continue,
             };
             // already has key
             if props.as_ref().map_or(false, find_key_on_js) {
-                return;
+                continue;
             }
             // inject default key
             let key = Js::str_lit("key");
@@ -40,6 +55,10 @@ impl<'a> CorePass<BaseInfo<'a>> for PatchFlagMarker {
         }
     }
     fn exit_vnode(&mut self, vn: &mut BaseVNode<'a>) {
+        // hydration needs to attach listeners to elements
+        if !vn.is_component && matches!(&vn.props, Some(p) if has_hydration_event(p)) {
+            vn.patch_flag |= PatchFlag::HYDRATE_EVENTS;
+        }
         if vn.children.len() != 1 || is_builtin_symbol(&vn.tag, RH::TELEPORT) {
             return;
         }
@@ -79,6 +98,22 @@ impl<'a> CorePass<BaseInfo<'a>> for PatchFlagMarker {
     }
 }
 
+fn has_hydration_event(props: &Js) -> bool {
+    match props {
+        Js::Props(ps) => ps.iter().any(|(k, _)| match k {
+            // v-model update and vnode hooks are not DOM events
+            Js::StrLit(k) => {
+                VStr::is_handler(k) && !VStr::is_model_handler(k) && !is_reserved_prop(k)
+            }
+            _ => false,
+        }),
+        Js::Call(RH::MERGE_PROPS | RH::NORMALIZE_PROPS, args) => {
+            args.iter().any(has_hydration_event)
+        }
+        _ => false,
+    }
+}
+
 fn find_prop<'a, 'b>(t: &'b BaseIR<'a>) -> Option<&'b Js<'a>> {
     match t {
         IR::VNodeCall(v) => v.props.as_ref(),
@@ -119,27 +154,86 @@ fn find_key_on_js(e: &Js) -> bool {
 
 // 1. mergeProps(...)
 // 2. toHandlers(...)
+/// Injected key always comes first so prop order stays deterministic:
+/// synthetic key, then source order props.
 fn inject_prop<'a>(props: &mut Js<'a>, key: Prop<'a>) {
     debug_assert!(!find_key_on_js(props));
     match props {
         Js::Call(RH::MERGE_PROPS, args) => {
-            for arg in args.iter_mut() {
-                if let Js::Props(ps) = arg {
-                    ps.push(key);
-                    return;
-                }
+            if let Some(Js::Props(ps)) = args.first_mut() {
+                ps.insert(0, key);
+            } else {
+                args.insert(0, Js::Props(vec![key]));
             }
-            args.push(Js::Props(vec![key]));
         }
-        Js::Props(ps) => ps.push(key),
+        Js::Props(ps) => ps.insert(0, key),
         // should not inject props to any other expression
         obj => {
-            return {
-                let mut temp = Js::Src("");
-                std::mem::swap(obj, &mut temp);
-                let p = Js::Props(vec![key]);
-                *obj = Js::Call(RH::MERGE_PROPS, vec![temp, p]);
-            }
+            let temp = std::mem::take(obj);
+            let p = Js::Props(vec![key]);
+            *obj = Js::Call(RH::MERGE_PROPS, vec![p, temp]);
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::super::test::base_convert;
+    use super::*;
+    use crate::transformer::{BaseTransformer, Transformer};
+
+    fn branch_has_key(s: &str, inject_key: bool) -> bool {
+        nth_branch_has_key(s, inject_key, 0)
+    }
+
+    fn nth_branch_has_key(s: &str, inject_key: bool, n: usize) -> bool {
+        let mut ir = base_convert(s);
+        BaseTransformer::transform(&mut ir, PatchFlagMarker::new(inject_key));
+        let i = match &ir.body[0] {
+            IR::If(i) => i,
+            _ => panic!("should be v-if"),
+        };
+        match &*i.branches[n].child {
+            IR::For(f) => f.key.is_some(),
+            child => find_key(child),
+        }
+    }
+
+    #[test]
+    fn test_inject_key() {
+        assert!(branch_has_key("<p v-if='a'/>", true));
+        assert!(branch_has_key("<p v-if='a' v-for='i in b'/>", true));
+    }
+
+    #[test]
+    fn test_inject_key_after_keyed_branch() {
+        let case = "<p v-if='a' :key='k'/><i v-else-if='b' v-for='c in d'/><b v-else/>";
+        assert!(nth_branch_has_key(case, true, 1));
+        assert!(nth_branch_has_key(case, true, 2));
+    }
+
+    fn for_flag(s: &str) -> (i32, bool) {
+        let mut ir = base_convert(s);
+        BaseTransformer::transform(&mut ir, PatchFlagMarker::new(true));
+        let f = match &ir.body[0] {
+            IR::For(f) => f,
+            _ => panic!("should be v-for"),
+        };
+        (f.fragment_flag.bits(), f.is_stable)
+    }
+
+    #[test]
+    fn test_for_fragment_flag() {
+        assert_eq!(for_flag("<p v-for='a in b' :key='a'/>"), (128, false));
+        assert_eq!(for_flag("<p v-for='a in b' key='k'/>"), (128, false));
+        assert_eq!(for_flag("<p v-for='a in b'/>"), (256, false));
+        assert_eq!(for_flag("<p v-for='a in b' :id='a'/>"), (256, false));
+    }
+
+    #[test]
+    fn test_no_inject_key() {
+        assert!(!branch_has_key("<p v-if='a'/>", false));
+        assert!(!branch_has_key("<p v-if='a' v-for='i in b'/>", false));
+        assert!(!branch_has_key("<p v-if='a' :class='c'/>", false));
+    }
+}
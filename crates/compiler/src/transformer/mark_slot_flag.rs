@@ -92,6 +92,7 @@ mod test {
             prefix_identifier: true,
             sfc_info: &Default::default(),
             err_handle: Rc::new(NoopErrorHandler),
+            global_allow_list: Default::default(),
         };
         let a = chain![marker, exp];
         let pass = transformer_ext(a);
@@ -24,21 +24,63 @@ seems patch flag can be extracted out
 pub mod cache_handlers;
 pub mod collect_entities;
 pub mod hoist_static;
+pub mod lift_temps;
 pub mod mark_patch_flag;
 pub mod mark_slot_flag;
 pub mod normalize_props;
 pub mod optimize_text;
 pub mod pass;
 pub mod process_expression;
+pub mod track_depth;
+pub mod warn_constant;
+pub mod warn_dynamic_props;
 
+use std::cell::Cell;
 use std::marker::PhantomData;
+use std::rc::Rc;
 
 use crate::{
     converter::{BaseConvertInfo as BaseInfo, BaseRoot},
+    error::{CompilationError, CompilationErrorKind as ErrorKind, NoopErrorHandler, RcErrHandle},
     ir::{self as C, ConvertInfo, IRNode, IRRoot, JsExpr as Js, RuntimeDir},
 };
 pub use pass::{CorePass, CorePassExt, Scope};
 
+/// Limit of IR nesting visited by transformers. Deeper nodes are skipped
+/// and NestingTooDeep is reported once, guarding the recursion against
+/// IR that is nested deeper than parsed elements, e.g. by v-if/v-for.
+pub struct DepthLimit {
+    max_depth: usize,
+    err_handle: RcErrHandle,
+    reported: Cell<bool>,
+}
+
+impl DepthLimit {
+    pub fn new(max_depth: usize, err_handle: RcErrHandle) -> Self {
+        Self {
+            max_depth,
+            err_handle,
+            reported: Cell::new(false),
+        }
+    }
+    fn exceeded(&self, level: usize) -> bool {
+        if level <= self.max_depth {
+            return false;
+        }
+        if !self.reported.replace(true) {
+            let error = CompilationError::new(ErrorKind::NestingTooDeep);
+            self.err_handle.on_error(error);
+        }
+        true
+    }
+}
+
+impl Default for DepthLimit {
+    fn default() -> Self {
+        Self::new(128, Rc::new(NoopErrorHandler))
+    }
+}
+
 pub trait Transformer<P> {
     type IR;
     /// transform will change ir node inplace
@@ -50,6 +92,8 @@ pub trait Transformer<P> {
 pub struct TransformOption {
     pub prefix_identifier: bool,
     pub is_dev: bool,
+    pub report_depth: bool,
+    pub inject_key: bool,
 }
 
 pub type BaseText<'a> = C::TextIR<BaseInfo<'a>>;
@@ -61,28 +105,47 @@ pub type BaseVSlot<'a> = C::VSlotIR<BaseInfo<'a>>;
 pub type BaseSlotFn<'a> = C::Slot<BaseInfo<'a>>;
 pub type BaseCache<'a> = C::CacheIR<BaseInfo<'a>>;
 
+#[derive(Clone, Copy)]
+struct Depth<'l> {
+    level: usize,
+    limit: &'l DepthLimit,
+}
+
+impl<'l> Depth<'l> {
+    fn deeper(self) -> Self {
+        Self {
+            level: self.level + 1,
+            ..self
+        }
+    }
+}
+
 trait CoreTransformer<T: ConvertInfo, P: CorePass<T>>: Transformer<P> {
-    fn transform_root(root: &mut IRRoot<T>, ps: &mut P);
+    fn transform_root(root: &mut IRRoot<T>, ps: &mut P, limit: &DepthLimit);
     fn transform_js_expr(e: &mut T::JsExpression, ps: &mut P);
 
-    fn transform_ir(ir: &mut IRNode<T>, ps: &mut P) {
+    /// depth counts the IR nodes enclosing ir.
+    fn transform_ir(ir: &mut IRNode<T>, ps: &mut P, depth: Depth) {
+        if depth.limit.exceeded(depth.level) {
+            return;
+        }
         use IRNode as I;
         match ir {
             I::TextCall(t) => Self::transform_text(t, ps),
-            I::If(i) => Self::transform_if(i, ps),
-            I::For(f) => Self::transform_for(f, ps),
-            I::VNodeCall(v) => Self::transform_vnode(v, ps),
-            I::RenderSlotCall(r) => Self::transform_slot_outlet(r, ps),
+            I::If(i) => Self::transform_if(i, ps, depth),
+            I::For(f) => Self::transform_for(f, ps, depth),
+            I::VNodeCall(v) => Self::transform_vnode(v, ps, depth),
+            I::RenderSlotCall(r) => Self::transform_slot_outlet(r, ps, depth),
             I::CommentCall(c) => Self::transform_comment(c, ps),
-            I::VSlotUse(s) => Self::transform_v_slot(s, ps),
-            I::CacheNode(c) => Self::transform_cache(c, ps),
-            I::AlterableSlot(a) => Self::transform_slot_fn(a, ps),
+            I::VSlotUse(s) => Self::transform_v_slot(s, ps, depth),
+            I::CacheNode(c) => Self::transform_cache(c, ps, depth),
+            I::AlterableSlot(a) => Self::transform_slot_fn(a, ps, depth),
             I::Hoisted(h) => Self::transform_hoisted(h, ps),
         }
     }
-    fn transform_children(children: &mut Vec<IRNode<T>>, ps: &mut P) {
+    fn transform_children(children: &mut Vec<IRNode<T>>, ps: &mut P, depth: Depth) {
         for child in children.iter_mut() {
-            Self::transform_ir(child, ps);
+            Self::transform_ir(child, ps, depth);
         }
     }
     fn transform_text(t: &mut C::TextIR<T>, ps: &mut P) {
@@ -92,17 +155,17 @@ trait CoreTransformer<T: ConvertInfo, P: CorePass<T>>: Transformer<P> {
         }
         ps.exit_text(t);
     }
-    fn transform_if(i: &mut C::IfNodeIR<T>, ps: &mut P) {
+    fn transform_if(i: &mut C::IfNodeIR<T>, ps: &mut P, depth: Depth) {
         ps.enter_if(i);
         for branch in i.branches.iter_mut() {
             if let Some(c) = branch.condition.as_mut() {
                 Self::transform_js_expr(c, ps);
             }
-            Self::transform_ir(&mut branch.child, ps);
+            Self::transform_ir(&mut branch.child, ps, depth.deeper());
         }
         ps.exit_if(i);
     }
-    fn transform_for(f: &mut C::ForNodeIR<T>, ps: &mut P) {
+    fn transform_for(f: &mut C::ForNodeIR<T>, ps: &mut P, depth: Depth) {
         // 1. first transform source in for node
         Self::transform_js_expr(&mut f.source, ps);
         use crate::ir::ForParseResult;
@@ -119,7 +182,7 @@ trait CoreTransformer<T: ConvertInfo, P: CorePass<T>>: Transformer<P> {
 
         // 3. the for itsel
         ps.enter_for(f);
-        Self::transform_ir(&mut f.child, ps);
+        Self::transform_ir(&mut f.child, ps, depth.deeper());
         ps.exit_for(f);
 
         let ForParseResult { value, key, index } = &mut f.parse_result;
@@ -134,13 +197,13 @@ trait CoreTransformer<T: ConvertInfo, P: CorePass<T>>: Transformer<P> {
         Self::transform_js_expr(value, ps);
         ps.exit_fn_param(value);
     }
-    fn transform_vnode(v: &mut C::VNodeIR<T>, ps: &mut P) {
+    fn transform_vnode(v: &mut C::VNodeIR<T>, ps: &mut P, depth: Depth) {
         ps.enter_vnode(v);
         Self::transform_js_expr(&mut v.tag, ps);
         if let Some(props) = v.props.as_mut() {
             Self::transform_js_expr(props, ps);
         }
-        Self::transform_children(&mut v.children, ps);
+        Self::transform_children(&mut v.children, ps, depth.deeper());
         for dir in v.directives.iter_mut() {
             Self::transform_runtime_dir(dir, ps);
         }
@@ -158,26 +221,27 @@ trait CoreTransformer<T: ConvertInfo, P: CorePass<T>>: Transformer<P> {
             Self::transform_js_expr(mods, ps);
         }
     }
-    fn transform_slot_outlet(r: &mut C::RenderSlotIR<T>, ps: &mut P) {
+    fn transform_slot_outlet(r: &mut C::RenderSlotIR<T>, ps: &mut P, depth: Depth) {
         ps.enter_slot_outlet(r);
+        Self::transform_js_expr(&mut r.slot_obj, ps);
         Self::transform_js_expr(&mut r.slot_name, ps);
         if let Some(props) = r.slot_props.as_mut() {
             Self::transform_js_expr(props, ps);
         }
-        Self::transform_children(&mut r.fallbacks, ps);
+        Self::transform_children(&mut r.fallbacks, ps, depth.deeper());
         ps.exit_slot_outlet(r);
     }
-    fn transform_v_slot(s: &mut C::VSlotIR<T>, ps: &mut P) {
+    fn transform_v_slot(s: &mut C::VSlotIR<T>, ps: &mut P, depth: Depth) {
         ps.enter_v_slot(s);
         for slot in s.stable_slots.iter_mut() {
-            Self::transform_slot_fn(slot, ps);
+            Self::transform_slot_fn(slot, ps, depth.deeper());
         }
         for slot in s.alterable_slots.iter_mut() {
-            Self::transform_ir(slot, ps);
+            Self::transform_ir(slot, ps, depth.deeper());
         }
         ps.exit_v_slot(s);
     }
-    fn transform_slot_fn(slot: &mut C::Slot<T>, ps: &mut P) {
+    fn transform_slot_fn(slot: &mut C::Slot<T>, ps: &mut P, depth: Depth) {
         ps.enter_slot_fn(slot);
         Self::transform_js_expr(&mut slot.name, ps);
         // slot param as fn_param, note: visit param after slot_fn
@@ -185,14 +249,14 @@ trait CoreTransformer<T: ConvertInfo, P: CorePass<T>>: Transformer<P> {
         if let Some(p) = &mut slot.param {
             ps.enter_fn_param(p);
         }
-        Self::transform_children(&mut slot.body, ps);
+        Self::transform_children(&mut slot.body, ps, depth.deeper());
         if let Some(p) = &mut slot.param {
             Self::transform_js_expr(p, ps);
             ps.exit_fn_param(p);
         }
         ps.exit_slot_fn(slot);
     }
-    fn transform_cache(cache: &mut C::CacheIR<T>, ps: &mut P) {
+    fn transform_cache(cache: &mut C::CacheIR<T>, ps: &mut P, depth: Depth) {
         ps.enter_cache(cache);
         use C::CacheKind as CK;
         match &mut cache.kind {
@@ -205,7 +269,7 @@ trait CoreTransformer<T: ConvertInfo, P: CorePass<T>>: Transformer<P> {
             }
             CK::Once => (), // nothing
         }
-        Self::transform_ir(&mut cache.child, ps);
+        Self::transform_ir(&mut cache.child, ps, depth.deeper());
         ps.exit_cache(cache);
     }
     fn transform_comment(c: &mut T::CommentType, ps: &mut P) {
@@ -222,8 +286,17 @@ pub struct BaseTransformer<'a>(pub PhantomData<&'a ()>);
 
 impl<'a, P: CorePass<BaseInfo<'a>>> Transformer<P> for BaseTransformer<'a> {
     type IR = BaseRoot<'a>;
-    fn transform(node: &mut Self::IR, mut pass: P) {
-        Self::transform_root(node, &mut pass);
+    fn transform(node: &mut Self::IR, pass: P) {
+        Self::transform_with_limit(node, pass, &DepthLimit::default());
+    }
+}
+
+impl<'a> BaseTransformer<'a> {
+    pub fn transform_with_limit<P>(node: &mut BaseRoot<'a>, mut pass: P, limit: &DepthLimit)
+    where
+        P: CorePass<BaseInfo<'a>>,
+    {
+        Self::transform_root(node, &mut pass, limit);
     }
 }
 
@@ -231,9 +304,10 @@ impl<'a, P> CoreTransformer<BaseInfo<'a>, P> for BaseTransformer<'a>
 where
     P: CorePass<BaseInfo<'a>>,
 {
-    fn transform_root(r: &mut IRRoot<BaseInfo<'a>>, ps: &mut P) {
+    fn transform_root(r: &mut IRRoot<BaseInfo<'a>>, ps: &mut P, limit: &DepthLimit) {
         ps.enter_root(r);
-        Self::transform_children(&mut r.body, ps);
+        let depth = Depth { level: 0, limit };
+        Self::transform_children(&mut r.body, ps, depth);
         ps.exit_root(r);
     }
 
@@ -297,4 +371,59 @@ mod test {
             pd: PhantomData,
         }
     }
+
+    #[derive(Default)]
+    struct SlotCounter {
+        ifs: usize,
+        slot_fns: usize,
+    }
+    impl<'a> CorePass<BaseInfo<'a>> for SlotCounter {
+        fn enter_if(&mut self, _: &mut BaseIf<'a>) {
+            self.ifs += 1;
+        }
+        fn enter_slot_fn(&mut self, _: &mut BaseSlotFn<'a>) {
+            self.slot_fns += 1;
+        }
+    }
+
+    #[test]
+    fn test_transform_alterable_slot() {
+        let mut ir = base_convert(
+            r#"<comp>
+                <template #a v-if="x">a</template>
+                <template v-for="i in l" #[i]>b</template>
+            </comp>"#,
+        );
+        let mut counter = SlotCounter::default();
+        BaseTransformer::transform_root(&mut ir, &mut counter, &DepthLimit::default());
+        assert_eq!(counter.ifs, 1);
+        assert_eq!(counter.slot_fns, 2);
+    }
+
+    struct VNodeCounter(usize);
+    impl<'a> CorePass<BaseInfo<'a>> for VNodeCounter {
+        fn enter_vnode(&mut self, _: &mut BaseVNode<'a>) {
+            self.0 += 1;
+        }
+    }
+
+    #[test]
+    fn test_depth_limit() {
+        use crate::error::VecErrorHandler;
+        let count = |max_depth| {
+            let eh = Rc::new(VecErrorHandler::new());
+            let mut ir = base_convert("<div><p><span/><i/></p></div>");
+            let mut counter = VNodeCounter(0);
+            let limit = DepthLimit::new(max_depth, eh.clone());
+            BaseTransformer::transform_root(&mut ir, &mut counter, &limit);
+            let errors = eh.errors();
+            assert!(errors
+                .iter()
+                .all(|e| matches!(e.kind, ErrorKind::NestingTooDeep)));
+            (counter.0, errors.len())
+        };
+        assert_eq!(count(2), (4, 0));
+        assert_eq!(count(1), (2, 1));
+        assert_eq!(count(0), (1, 1));
+    }
 }
@@ -1,5 +1,5 @@
 /// extract class/style for faster runtime patching
-use crate::ir::JsExpr as Js;
+use crate::ir::{JsExpr as Js, Prop};
 use crate::flags::RuntimeHelper as RH;
 use super::{BaseInfo, BaseVNode, CorePass};
 
@@ -14,7 +14,20 @@ impl<'a> CorePass<BaseInfo<'a>> for NormalizeProp {
             None => return,
         };
         match props {
-            Js::Call(..) => (), // nothing! MergeProps/toHandlers call
+            // mergeProps concats class/style in argument order, i.e.
+            // static < :class < spread. Normalize each object argument.
+            // A dynamic key may also be class/style at runtime, so leave
+            // such arguments for mergeProps to normalize.
+            Js::Call(RH::MERGE_PROPS, args) => {
+                for arg in args.iter_mut() {
+                    if let Js::Props(ps) = arg {
+                        if !has_dynamic_key(ps) {
+                            normalize_class_style(ps);
+                        }
+                    }
+                }
+            }
+            Js::Call(..) => (), // nothing! toHandlers call
             Js::Props(ps) => {
                 let ps = mem::take(ps);
                 *props = pre_normalize_prop(ps);
@@ -34,36 +47,163 @@ fn is_handler_expr(j: &Js) -> bool {
     matches!(j, Js::FuncSimple { .. }) || matches!(j, Js::FuncCompound { .. })
 }
 
-fn pre_normalize_prop<'a>(mut props: Vec<(Js<'a>, Js<'a>)>) -> Js<'a> {
-    let mut class_val = None;
-    let mut style_val = None;
-    let mut has_dynamic_key = false;
+fn has_dynamic_key(props: &[Prop]) -> bool {
+    props
+        .iter()
+        .any(|(key, val)| !matches!(key, Js::StrLit(_)) && !is_handler_expr(val))
+}
+
+fn pre_normalize_prop(mut props: Vec<Prop>) -> Js {
+    if has_dynamic_key(&props) {
+        return Js::Call(RH::NORMALIZE_PROPS, vec![Js::Props(props)]);
+    }
+    normalize_class_style(&mut props);
+    Js::Props(props)
+}
+
+fn normalize_class_style(props: &mut [Prop]) {
     for (key, val) in props.iter_mut() {
-        if let Js::StrLit(k) = key {
-            if k.raw == "class" {
-                class_val = Some(val);
-            } else if k.raw == "style" {
-                style_val = Some(val);
+        let helper = match key {
+            Js::StrLit(k) if k.raw == "class" && !matches!(val, Js::StrLit(..)) => {
+                RH::NORMALIZE_CLASS
             }
-        } else if !is_handler_expr(val) {
-            has_dynamic_key = true;
-        }
+            // static style string or Props parsed from it needs no normalization
+            Js::StrLit(k) if k.raw == "style" && !matches!(val, Js::StrLit(..) | Js::Props(..)) => {
+                RH::NORMALIZE_STYLE
+            }
+            _ => continue,
+        };
+        let v = mem::take(val);
+        *val = Js::Call(helper, vec![v]);
     }
-    if has_dynamic_key {
-        return Js::Call(RH::NORMALIZE_PROPS, vec![Js::Props(props)]);
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::test::base_convert;
+    use super::*;
+    use crate::{
+        chain,
+        converter::BaseRoot,
+        flags::PatchFlag,
+        ir::IRNode,
+        transformer::{collect_entities::EntityCollector, BaseTransformer, Transformer},
+    };
+
+    fn transform(s: &str) -> BaseRoot<'_> {
+        let mut ir = base_convert(s);
+        let passes = chain![EntityCollector::default(), NormalizeProp];
+        BaseTransformer::transform(&mut ir, passes);
+        ir
     }
-    if let Some(cls) = class_val {
-        if !matches!(cls, Js::StrLit(..)) {
-            let val = mem::take(cls);
-            *cls = Js::Call(RH::NORMALIZE_CLASS, vec![val]);
-        }
+
+    #[test]
+    fn test_merge_class_with_spread() {
+        let ir = transform("<p class='a' :class='b' v-bind='obj'/>");
+        let vn = cast!(&ir.body[0], IRNode::VNodeCall);
+        let args = match vn.props.as_ref().unwrap() {
+            Js::Call(RH::MERGE_PROPS, args) => args,
+            _ => panic!("class with spread should be merged"),
+        };
+        assert_eq!(args.len(), 2);
+        // static class comes before :class, spread comes last
+        let ps = cast!(&args[0], Js::Props);
+        assert_eq!(ps.len(), 1);
+        let cls = match &ps[0].1 {
+            Js::Call(RH::NORMALIZE_CLASS, cls) => cast!(&cls[0], Js::Array),
+            _ => panic!("class should be normalized"),
+        };
+        assert_eq!(cast!(&cls[0], Js::StrLit).raw, "a");
+        assert_eq!(cast!(&cls[1], Js::Simple).raw, "b");
+        assert_eq!(cast!(&args[1], Js::Simple).raw, "obj");
+        let helpers = &ir.top_scope.helpers;
+        assert!(helpers.contains(RH::MERGE_PROPS));
+        assert!(helpers.contains(RH::NORMALIZE_CLASS));
     }
-    if let Some(stl) = style_val {
-        // Props is parsed from literal style string
-        if !matches!(stl, Js::Props(..)) {
-            let val = mem::take(stl);
-            *stl = Js::Call(RH::NORMALIZE_STYLE, vec![val]);
-        }
+
+    #[test]
+    fn test_dynamic_arg_not_normalized() {
+        let ir = transform("<p :[maybeClass]='v'/>");
+        let vn = cast!(&ir.body[0], IRNode::VNodeCall);
+        assert!(vn.patch_flag == PatchFlag::FULL_PROPS);
+        let args = match vn.props.as_ref().unwrap() {
+            Js::Call(RH::NORMALIZE_PROPS, args) => args,
+            _ => panic!("dynamic key should be normalized at runtime"),
+        };
+        let ps = cast!(&args[0], Js::Props);
+        assert_eq!(cast!(&ps[0].1, Js::Simple).raw, "v");
+        assert!(!ir.top_scope.helpers.contains(RH::NORMALIZE_CLASS));
+    }
+
+    #[test]
+    fn test_merge_dynamic_arg_not_normalized() {
+        let ir = transform("<p :class='c' :[k]='v' v-bind='obj'/>");
+        let vn = cast!(&ir.body[0], IRNode::VNodeCall);
+        assert!(vn.patch_flag == PatchFlag::FULL_PROPS);
+        let args = match vn.props.as_ref().unwrap() {
+            Js::Call(RH::MERGE_PROPS, args) => args,
+            _ => panic!("props with spread should be merged"),
+        };
+        let ps = cast!(&args[0], Js::Props);
+        assert_eq!(cast!(&ps[0].1, Js::Simple).raw, "c");
+        assert!(!ir.top_scope.helpers.contains(RH::NORMALIZE_CLASS));
+    }
+
+    #[test]
+    fn test_string_concat_style() {
+        let ir = transform("<p :style=\"'color:' + c\"/>");
+        let vn = cast!(&ir.body[0], IRNode::VNodeCall);
+        assert!(vn.patch_flag.contains(PatchFlag::STYLE));
+        let ps = cast!(vn.props.as_ref().unwrap(), Js::Props);
+        assert_eq!(cast!(&ps[0].0, Js::StrLit).raw, "style");
+        let style = match &ps[0].1 {
+            Js::Call(RH::NORMALIZE_STYLE, style) => cast!(&style[0], Js::Simple),
+            _ => panic!("style should be normalized"),
+        };
+        assert_eq!(style.raw, "'color:' + c");
+        assert!(ir.top_scope.helpers.contains(RH::NORMALIZE_STYLE));
+    }
+
+    fn get_style<'a, 'b>(ir: &'b BaseRoot<'a>) -> &'b Js<'a> {
+        let vn = cast!(&ir.body[0], IRNode::VNodeCall);
+        let ps = cast!(vn.props.as_ref().unwrap(), Js::Props);
+        assert_eq!(cast!(&ps[0].0, Js::StrLit).raw, "style");
+        &ps[0].1
+    }
+
+    #[test]
+    fn test_object_style() {
+        let ir = transform("<p :style='{ color: c }'/>");
+        let style = match get_style(&ir) {
+            Js::Call(RH::NORMALIZE_STYLE, style) => cast!(&style[0], Js::Simple),
+            _ => panic!("style should be normalized"),
+        };
+        assert_eq!(style.raw, "{ color: c }");
+        assert!(ir.top_scope.helpers.contains(RH::NORMALIZE_STYLE));
+    }
+
+    #[test]
+    fn test_merge_static_style() {
+        let ir = transform("<p style='color: red' :style='[base, override]'/>");
+        let style = match get_style(&ir) {
+            Js::Call(RH::NORMALIZE_STYLE, style) => cast!(&style[0], Js::Array),
+            _ => panic!("style should be normalized"),
+        };
+        assert_eq!(cast!(&style[0], Js::StrLit).raw, "color: red");
+        assert_eq!(cast!(&style[1], Js::Simple).raw, "[base, override]");
+    }
+
+    #[test]
+    fn test_static_style_not_normalized() {
+        let ir = transform("<p style='color: red'/>");
+        let style = cast!(get_style(&ir), Js::StrLit);
+        assert_eq!(style.raw, "color: red");
+        assert!(!ir.top_scope.helpers.contains(RH::NORMALIZE_STYLE));
+    }
+
+    #[test]
+    fn test_static_class_not_normalized() {
+        let ir = transform("<p class='a' v-bind='obj'/>");
+        assert!(!ir.top_scope.helpers.contains(RH::NORMALIZE_CLASS));
     }
-    Js::Props(props)
 }
@@ -1,9 +1,6 @@
-use smallvec::SmallVec;
-
 use super::{BaseInfo, BaseRenderSlot, BaseSlotFn, BaseVNode, CorePass, IRNode as IR};
 use crate::cast;
 use crate::converter::{BaseIR, BaseRoot};
-use crate::ir::JsExpr as Js;
 
 pub struct TextOptimizer;
 
@@ -39,14 +36,15 @@ fn merge_consecutive_calls(cs: &mut Vec<BaseIR>) {
             continue;
         }
         let (left, right) = cs.split_at_mut(i + 1);
-        let dest = must_text(&mut left[i]);
+        let dest = cast!(&mut left[i], IR::TextCall);
         let mut j = 0;
         while j < right.len() {
             if !matches!(&right[j], IR::TextCall(_)) {
                 break;
             }
-            let src = must_text(&mut right[j]);
-            dest.extend(src.drain(..));
+            let src = cast!(&mut right[j], IR::TextCall);
+            dest.texts.extend(src.texts.drain(..));
+            dest.locs.extend(src.locs.drain(..));
             j += 1;
         }
         drop(cs.drain(i + 1..i + 1 + j));
@@ -67,21 +65,22 @@ fn optimize_away_call(cs: &mut Vec<BaseIR>) {
     }
 }
 
-fn must_text<'a, 'b>(a: &'b mut BaseIR<'a>) -> &'b mut SmallVec<[Js<'a>; 1]> {
-    let t = cast!(a, IR::TextCall);
-    &mut t.texts
-}
-
 #[cfg(test)]
 mod test {
     use super::super::test::base_convert;
     use crate::transformer::BaseTransformer;
     use super::super::{BaseText, Transformer};
     use super::*;
+    use crate::ir::JsExpr as Js;
+    use smallvec::SmallVec;
 
     fn must_ir<'a, 'b>(a: &'b BaseIR<'a>) -> &'b BaseText<'a> {
         cast!(a, IR::TextCall)
     }
+    fn must_text<'a, 'b>(a: &'b mut BaseIR<'a>) -> &'b mut SmallVec<[Js<'a>; 1]> {
+        let t = cast!(a, IR::TextCall);
+        &mut t.texts
+    }
 
     #[test]
     fn test_merge_text() {
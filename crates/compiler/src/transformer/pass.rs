@@ -1,6 +1,7 @@
 use std::marker::PhantomData;
 
 use super::{BaseInfo, BaseTransformer, BaseVNode, ConvertInfo, CoreTransformer, Js, C};
+use super::{Depth, DepthLimit};
 use crate::Name;
 use rustc_hash::FxHashMap;
 
@@ -143,14 +144,26 @@ impl<'a> Scope<'a> {
         *self.identifiers.entry(id).or_default() += 1;
     }
     pub fn remove_identifier(&mut self, id: Name<'a>) {
-        *self.identifiers.entry(id).or_default() -= 1;
+        // same name can be shadowed in nested scope. decrement the shadowing
+        // count and remove the entry only when no scope declares it anymore
+        if let Some(count) = self.identifiers.get_mut(id) {
+            *count -= 1;
+            if *count == 0 {
+                self.identifiers.remove(id);
+            }
+        }
     }
     pub fn has_ref_in_vnode(&self, node: &mut BaseVNode<'a>) -> bool {
         if self.identifiers.is_empty() {
             return false;
         }
         let mut ref_finder = RefFinder(&self.identifiers, false);
-        BaseTransformer::transform_vnode(node, &mut ref_finder);
+        let limit = DepthLimit::default();
+        let depth = Depth {
+            level: 0,
+            limit: &limit,
+        };
+        BaseTransformer::transform_vnode(node, &mut ref_finder, depth);
         ref_finder.1
     }
     pub fn has_ref_in_expr(&self, exp: &mut Js<'a>) -> bool {
@@ -10,11 +10,14 @@ use crate::ir::JsExpr as Js;
 use crate::util::{is_global_allow_listed, is_simple_identifier, rslint, VStr};
 use crate::{cast, BindingTypes, SFCInfo, SourceLocation};
 use crate::error::NoopErrorHandler;
+use rustc_hash::FxHashSet;
 
 pub struct ExpressionProcessor<'a, 'b> {
     pub prefix_identifier: bool,
     pub sfc_info: &'b SFCInfo<'a>,
     pub err_handle: RcErrHandle,
+    /// extra globals left un-prefixed besides the built-in allow list
+    pub global_allow_list: FxHashSet<String>,
 }
 
 impl<'a, 'b> CorePassExt<BaseInfo<'a>, Scope<'a>> for ExpressionProcessor<'a, 'b> {
@@ -78,7 +81,7 @@ impl<'a, 'b> ExpressionProcessor<'a, 'b> {
             }
         })
     }
-    fn process_expression(&self, e: &mut Js<'a>, scope: &mut Scope) {
+    pub(crate) fn process_expression(&self, e: &mut Js<'a>, scope: &mut Scope) {
         if !self.prefix_identifier {
             return;
         }
@@ -122,12 +125,22 @@ impl<'a, 'b> ExpressionProcessor<'a, 'b> {
             prefix_identifier: true,
             sfc_info,
             err_handle: std::rc::Rc::new(NoopErrorHandler),
+            global_allow_list: FxHashSet::default(),
         };
         proc.process_expression(&mut e, &mut scope);
         e
     }
 
     fn process_simple_expr(&self, e: &mut Js<'a>, scope: &Scope) {
+        // v-model `(exp) = $event`: rewrite exp alone and keep the assignment
+        if let Js::Simple(v, lvl) = e {
+            if VStr::is_event_assign(v) {
+                let mut lhs = Js::Simple(VStr::raw(v.raw), *lvl);
+                self.process_simple_expr(&mut lhs, scope);
+                *e = Js::Compound(vec![Js::Src("("), lhs, Js::Src(") = $event")]);
+                return;
+            }
+        }
         if self.process_expr_fast(e, scope) {
             return;
         }
@@ -140,12 +153,14 @@ impl<'a, 'b> ExpressionProcessor<'a, 'b> {
             Js::Simple(v, level) => (v, level),
             _ => panic!("impossible"),
         };
-        if !is_simple_identifier(*v) {
+        // v-bind shorthand :foo-bar is camelized to fooBar
+        let camelized = VStr::is_camelized(v).then(|| v.into_string());
+        let raw_exp = camelized.as_deref().unwrap_or(v.raw);
+        if VStr::has_affix(v) || !is_simple_identifier(VStr::raw(raw_exp)) {
             return false;
         }
-        let raw_exp = v.raw;
         let is_scope_reference = scope.has_identifier(raw_exp);
-        let is_allowed_global = is_global_allow_listed(raw_exp);
+        let is_allowed_global = self.is_allowed_global(raw_exp);
         let is_literal = matches!(raw_exp, "true" | "false" | "null" | "this");
         if !is_scope_reference && !is_allowed_global && !is_literal {
             // const bindings from setup can skip patching but cannot be hoisted
@@ -216,6 +231,9 @@ impl<'a, 'b> ExpressionProcessor<'a, 'b> {
             Js::simple(*raw.clone().prefix_ctx())
         }
     }
+    fn is_allowed_global(&self, s: &str) -> bool {
+        is_global_allow_listed(s) || self.global_allow_list.contains(s)
+    }
     fn report_wrong_identifier(&self, loc: SourceLocation) {
         let error = CompilationError::new(ErrorKind::InvalidExpression).with_location(loc);
         self.err_handle.on_error(error);
@@ -227,13 +245,12 @@ impl<'a, 'b> ExpressionProcessor<'a, 'b> {
         scope: &Scope,
     ) -> Option<(FreeVarAtoms<'a>, bool)> {
         let expr = rslint::parse_js_expr(raw)?;
-        let inline = self.sfc_info.inline;
         let mut atoms = vec![];
         let mut has_local_ref = false;
         rslint::walk_free_variables(expr, |fv| {
             let id_text = fv.text();
             // skip global variable prefixing
-            if is_global_allow_listed(&id_text) || id_text == "require" {
+            if self.is_allowed_global(&id_text) || id_text == "require" {
                 return;
             }
             let range = fv.range();
@@ -242,7 +259,9 @@ impl<'a, 'b> ExpressionProcessor<'a, 'b> {
                 has_local_ref = true;
                 return;
             }
-            let ctx_type = if inline { todo!() } else { CtxType::NoWrite };
+            // TODO: detect assignment/update/destructure for setup let bindings.
+            // Reading is the common case: refs use `.value`, maybe-refs `unref`.
+            let ctx_type = CtxType::NoWrite;
             atoms.push(Atom {
                 range,
                 property: FreeVarProp {
@@ -426,6 +445,7 @@ mod test {
             prefix_identifier: true,
             sfc_info: &Default::default(),
             err_handle: handler,
+            global_allow_list: Default::default(),
         };
         let pass = transformer_ext(exp);
         BaseTransformer::transform(&mut ir, pass);
@@ -493,6 +513,83 @@ mod test {
         assert_eq!(b.into_string(), "_ctx.b");
     }
 
+    #[test]
+    fn test_custom_global_allow_list() {
+        let mut ir = base_convert("{{myGlobal + unknown}}");
+        let exp = ExpressionProcessor {
+            prefix_identifier: true,
+            sfc_info: &Default::default(),
+            err_handle: Rc::new(NoopErrorHandler),
+            global_allow_list: std::iter::once("myGlobal".into()).collect(),
+        };
+        BaseTransformer::transform(&mut ir, transformer_ext(exp));
+        let text = cast!(first_child(ir), IRNode::TextCall);
+        let text = match &text.texts[0] {
+            Js::Call(_, r) => &r[0],
+            _ => panic!("wrong interpolation"),
+        };
+        let expr = cast!(text, Js::Compound);
+        assert_eq!(cast!(expr[0], Js::Src), "myGlobal + ");
+        let b = cast!(expr[1], Js::Simple);
+        assert_eq!(b.into_string(), "_ctx.unknown");
+    }
+
+    fn inline_transform<'a>(s: &'a str, sfc_info: &SFCInfo<'a>) -> BaseRoot<'a> {
+        let mut ir = base_convert(s);
+        let exp = ExpressionProcessor {
+            prefix_identifier: true,
+            sfc_info,
+            err_handle: Rc::new(NoopErrorHandler),
+            global_allow_list: Default::default(),
+        };
+        BaseTransformer::transform(&mut ir, transformer_ext(exp));
+        ir
+    }
+    fn setup_info() -> SFCInfo<'static> {
+        let mut map = rustc_hash::FxHashMap::default();
+        map.insert("count", BindingTypes::SetupRef);
+        map.insert("c", BindingTypes::SetupConst);
+        map.insert("p", BindingTypes::Props);
+        SFCInfo {
+            inline: true,
+            binding_metadata: crate::BindingMetadata::new_setup(map),
+            ..Default::default()
+        }
+    }
+    fn interpolation<'a, 'b>(ir: &'b BaseRoot<'a>) -> &'b Js<'a> {
+        let text = cast!(&ir.body[0], IRNode::TextCall);
+        match &text.texts[0] {
+            Js::Call(_, r) => &r[0],
+            _ => panic!("wrong interpolation"),
+        }
+    }
+
+    #[test]
+    fn test_inline_setup_ref() {
+        let info = setup_info();
+        let ir = inline_transform("{{count}}", &info);
+        let expr = cast!(interpolation(&ir), Js::Compound);
+        assert_eq!(cast!(&expr[0], Js::Simple).raw, "count");
+        assert_eq!(cast!(&expr[1], Js::Src), &".value");
+        let ir = inline_transform("{{count + p}}", &info);
+        let expr = cast!(interpolation(&ir), Js::Compound);
+        let count = cast!(&expr[0], Js::Compound);
+        assert_eq!(cast!(&count[1], Js::Src), &".value");
+        let p = cast!(&expr[2], Js::Compound);
+        assert_eq!(cast!(&p[0], Js::Src), &"__props.");
+    }
+
+    #[test]
+    fn test_inline_setup_const() {
+        let info = setup_info();
+        let ir = inline_transform("{{c}}", &info);
+        let expr = cast!(interpolation(&ir), Js::Simple);
+        assert_eq!(expr.raw, "c");
+        let ir = inline_transform("{{c + 1}}", &info);
+        let expr = cast!(interpolation(&ir), Js::Compound);
+        assert_eq!(cast!(&expr[0], Js::Simple).raw, "c");
+    }
+
     #[test]
     fn test_transform_shorthand() {
         let ir = transform("{{ {a} }}");
@@ -549,6 +646,28 @@ mod test {
         assert_eq!(val.into_string(), "_ctx.c");
     }
 
+    #[test]
+    fn test_slot_param_shadowing() {
+        use super::super::{BaseText, CorePass, CoreTransformer, DepthLimit};
+        struct TextCollector(Vec<String>);
+        impl<'a> CorePass<BaseInfo<'a>> for TextCollector {
+            fn exit_text(&mut self, t: &mut BaseText<'a>) {
+                if let Js::Call(_, r) = &t.texts[0] {
+                    self.0.push(cast!(r[0], Js::Simple).into_string());
+                }
+            }
+        }
+        let mut ir = transform(
+            "<div>
+                <comp v-slot='s'><comp v-slot='s'>{{s}}</comp>{{s}}</comp>
+                {{s}}
+            </div>",
+        );
+        let mut collector = TextCollector(vec![]);
+        BaseTransformer::transform_root(&mut ir, &mut collector, &DepthLimit::default());
+        assert_eq!(collector.0, ["s", "s", "_ctx.s"]);
+    }
+
     #[test]
     fn test_error_expression() {
         let error_handler = Rc::new(VecErrorHandler::default());
@@ -0,0 +1,92 @@
+// Tracks how deep the traversal is in the IR tree.
+// Max depth is useful for performance tuning and guarding deep nesting.
+use super::{BaseInfo, CorePass, C};
+use crate::converter::BaseRoot;
+
+#[derive(Default)]
+pub struct DepthTracker {
+    /// write max depth into IRRoot's top scope after traversal
+    report: bool,
+    depth: usize,
+    max_depth: usize,
+}
+
+impl DepthTracker {
+    pub fn new(report: bool) -> Self {
+        Self {
+            report,
+            ..Default::default()
+        }
+    }
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+    fn enter(&mut self) {
+        self.depth += 1;
+        self.max_depth = self.max_depth.max(self.depth);
+    }
+    fn exit(&mut self) {
+        debug_assert!(self.depth > 0);
+        self.depth -= 1;
+    }
+}
+
+macro_rules! track_node {
+    ($enter: ident, $exit: ident, $ty: ident) => {
+        fn $enter(&mut self, _: &mut C::$ty<BaseInfo<'a>>) {
+            self.enter();
+        }
+        fn $exit(&mut self, _: &mut C::$ty<BaseInfo<'a>>) {
+            self.exit();
+        }
+    };
+}
+
+impl<'a> CorePass<BaseInfo<'a>> for DepthTracker {
+    track_node!(enter_text, exit_text, TextIR);
+    track_node!(enter_if, exit_if, IfNodeIR);
+    track_node!(enter_for, exit_for, ForNodeIR);
+    track_node!(enter_vnode, exit_vnode, VNodeIR);
+    track_node!(enter_slot_outlet, exit_slot_outlet, RenderSlotIR);
+    track_node!(enter_v_slot, exit_v_slot, VSlotIR);
+    track_node!(enter_slot_fn, exit_slot_fn, Slot);
+    track_node!(enter_cache, exit_cache, CacheIR);
+    track_node!(enter_comment, exit_comment, CommentType);
+    track_node!(enter_hoisted, exit_hoisted, HoistedIndex);
+
+    fn exit_root(&mut self, r: &mut BaseRoot<'a>) {
+        debug_assert_eq!(self.depth, 0);
+        if self.report {
+            r.top_scope.max_depth = Some(self.max_depth);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::test::base_convert;
+    use super::*;
+    use crate::transformer::{BaseTransformer, Transformer};
+
+    fn max_depth(s: &str, report: bool) -> Option<usize> {
+        let mut ir = base_convert(s);
+        BaseTransformer::transform(&mut ir, DepthTracker::new(report));
+        ir.top_scope.max_depth
+    }
+
+    #[test]
+    fn test_report_depth() {
+        assert_eq!(max_depth("<div><p><span/></p></div>", true), Some(3));
+        assert_eq!(max_depth("<div><p>text</p><br/></div>", true), Some(3));
+        assert_eq!(max_depth("<p v-if='a'><span/></p>", true), Some(3));
+        assert_eq!(max_depth("", true), Some(0));
+    }
+
+    #[test]
+    fn test_no_report() {
+        assert_eq!(max_depth("<div><p/></div>", false), None);
+    }
+}
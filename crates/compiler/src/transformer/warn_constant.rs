@@ -0,0 +1,102 @@
+// warn interpolations that are pure constants, e.g. {{ 1 + 1 }}.
+// they can be written as static text and be stringified/hoisted.
+// it relies on static level computed by ExpressionProcessor,
+// so it only works when prefix_identifier is enabled.
+use super::{BaseInfo, BaseText, CorePass};
+use crate::error::{CompilationError, CompilationErrorKind as ErrorKind, RcErrHandle};
+use crate::flags::{RuntimeHelper as RH, StaticLevel};
+use crate::ir::JsExpr as Js;
+
+pub struct ConstantWarner {
+    enabled: bool,
+    err_handle: RcErrHandle,
+}
+
+impl ConstantWarner {
+    pub fn new(enabled: bool, err_handle: RcErrHandle) -> Self {
+        Self {
+            enabled,
+            err_handle,
+        }
+    }
+}
+
+impl<'a> CorePass<BaseInfo<'a>> for ConstantWarner {
+    fn exit_text(&mut self, t: &mut BaseText<'a>) {
+        if !self.enabled {
+            return;
+        }
+        for (text, loc) in t.texts.iter().zip(t.locs.iter()) {
+            if is_constant_interpolation(text) {
+                let error = CompilationError::new(ErrorKind::ConstantInterpolation)
+                    .with_location(loc.clone());
+                self.err_handle.on_error(error);
+            }
+        }
+    }
+}
+
+fn is_constant_interpolation(e: &Js) -> bool {
+    match e {
+        // anything referencing _ctx is rewritten to NotStatic
+        Js::Call(RH::TO_DISPLAY_STRING, args) => args
+            .iter()
+            .all(|a| a.static_level() == StaticLevel::CanStringify),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::test::{base_convert, transformer_ext};
+    use super::super::{process_expression::ExpressionProcessor, BaseTransformer, Transformer};
+    use super::*;
+    use crate::chain;
+    use crate::error::VecErrorHandler;
+    use std::rc::Rc;
+
+    fn warnings(s: &str) -> Vec<CompilationError> {
+        let eh = Rc::new(VecErrorHandler::default());
+        let mut ir = base_convert(s);
+        let exp = ExpressionProcessor {
+            prefix_identifier: true,
+            sfc_info: &Default::default(),
+            err_handle: eh.clone(),
+            global_allow_list: Default::default(),
+        };
+        let pass = chain![ConstantWarner::new(true, eh.clone()), transformer_ext(exp)];
+        BaseTransformer::transform(&mut ir, pass);
+        let mut errors = eh.error_mut();
+        errors
+            .drain(..)
+            .filter(|e| matches!(e.kind, ErrorKind::ConstantInterpolation))
+            .collect()
+    }
+    fn warning_count(s: &str) -> usize {
+        warnings(s).len()
+    }
+
+    #[test]
+    fn test_constant_interpolation() {
+        assert_eq!(warning_count("{{ 'a' + 'b' }}"), 1);
+        assert_eq!(warning_count("{{ 1 + 1 }}"), 1);
+        assert_eq!(warning_count("<p>{{ true }}</p>"), 1);
+    }
+
+    #[test]
+    fn test_warning_location() {
+        let case = "a {{ b }} {{ 1 + 1 }}";
+        let errors = warnings(case);
+        assert_eq!(errors.len(), 1);
+        let loc = &errors[0].location;
+        assert_eq!(&case[loc.start.offset..loc.end.offset], "{{ 1 + 1 }}");
+    }
+
+    #[test]
+    fn test_ctx_interpolation() {
+        assert_eq!(warning_count("{{ msg }}"), 0);
+        assert_eq!(warning_count("{{ msg + 1 }}"), 0);
+        assert_eq!(warning_count("plain text"), 0);
+        assert_eq!(warning_count("<p v-for='a in b'>{{ a }}</p>"), 0);
+    }
+}
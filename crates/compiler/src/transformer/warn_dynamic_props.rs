@@ -0,0 +1,100 @@
+// warn elements whose PROPS patch flag tracks too many dynamic props, e.g.
+// <p :a="a" :b="b" :c="c" :d="d"/>. binding an object by v-bind is cheaper.
+// a preceding <!-- @vue-ignore --> comment suppresses the warning on the next
+// element, so comments must be preserved for the marker to work.
+use super::{BaseInfo, BaseText, BaseVNode, CorePass};
+use crate::error::{CompilationError, CompilationErrorKind as ErrorKind, RcErrHandle};
+use crate::flags::PatchFlag;
+use std::mem;
+
+pub const IGNORE_MARKER: &str = "@vue-ignore";
+
+pub struct DynamicPropsWarner {
+    max_props: Option<usize>,
+    err_handle: RcErrHandle,
+    // whether the next element is preceded by the marker comment
+    ignore_next: bool,
+}
+
+impl DynamicPropsWarner {
+    pub fn new(max_props: Option<usize>, err_handle: RcErrHandle) -> Self {
+        Self {
+            max_props,
+            err_handle,
+            ignore_next: false,
+        }
+    }
+}
+
+impl<'a> CorePass<BaseInfo<'a>> for DynamicPropsWarner {
+    fn enter_comment(&mut self, c: &mut &'a str) {
+        self.ignore_next = c.trim() == IGNORE_MARKER;
+    }
+    fn enter_text(&mut self, _: &mut BaseText<'a>) {
+        self.ignore_next = false;
+    }
+    fn enter_vnode(&mut self, v: &mut BaseVNode<'a>) {
+        // v-if/v-for wrapping the element keeps the marker
+        let ignored = mem::take(&mut self.ignore_next);
+        let max = match self.max_props {
+            Some(max) => max,
+            None => return,
+        };
+        if ignored || !v.patch_flag.contains(PatchFlag::PROPS) {
+            return;
+        }
+        if v.dynamic_props.len() > max {
+            let error = CompilationError::new(ErrorKind::TooManyDynamicProps)
+                .with_location((*v.loc).clone());
+            self.err_handle.on_error(error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{BaseTransformer, Transformer};
+    use super::*;
+    use crate::converter::test::base_convert;
+    use crate::error::VecErrorHandler;
+    use std::rc::Rc;
+
+    fn warning_count(s: &str, max_props: Option<usize>) -> usize {
+        let eh = Rc::new(VecErrorHandler::default());
+        let mut ir = base_convert(s);
+        let pass = DynamicPropsWarner::new(max_props, eh.clone());
+        BaseTransformer::transform(&mut ir, pass);
+        let errors = eh.errors();
+        errors
+            .iter()
+            .filter(|e| matches!(e.kind, ErrorKind::TooManyDynamicProps))
+            .count()
+    }
+
+    #[test]
+    fn test_too_many_dynamic_props() {
+        let case = "<p :a='a' :b='b' :c='c'/>";
+        assert_eq!(warning_count(case, Some(2)), 1);
+        assert_eq!(warning_count(case, Some(3)), 0);
+        assert_eq!(warning_count(case, None), 0);
+        assert_eq!(
+            warning_count("<p :class='a' :style='b' :c='c'/>", Some(0)),
+            1
+        );
+    }
+
+    #[test]
+    fn test_ignore_marker() {
+        let p = "<p :a='a' :b='b'/>";
+        let case = format!("<!-- @vue-ignore -->{}{}", p, p);
+        assert_eq!(warning_count(&case, Some(1)), 1);
+        let case = format!("<!--@vue-ignore--><div>{}</div>", p);
+        assert_eq!(warning_count(&case, Some(1)), 1);
+        let case = format!("<!-- @vue-ignore -->text{}", p);
+        assert_eq!(warning_count(&case, Some(1)), 1);
+        let case = "<!-- @vue-ignore --><p v-if='x' :a='a' :b='b'/>";
+        assert_eq!(warning_count(case, Some(1)), 0);
+        let case = format!("<!-- other -->{}", p);
+        assert_eq!(warning_count(&case, Some(1)), 1);
+    }
+}
@@ -1,11 +1,14 @@
+#[cfg(feature = "std")]
 use super::{
     converter::BaseConvertInfo,
-    flags::RuntimeHelper,
     ir::{JsExpr as Js, VNodeIR},
+};
+use super::{
+    flags::RuntimeHelper,
     parser::{Directive, DirectiveArg, ElemProp, Element},
     scanner::Attribute,
 };
-use std::{
+use core::{
     borrow::{Borrow, BorrowMut},
     cell::UnsafeCell,
     marker::PhantomData,
@@ -26,6 +29,7 @@ macro_rules! cast {
 mod decode_html;
 mod json;
 mod named_chars;
+#[cfg(feature = "std")]
 pub mod rslint;
 mod v_str;
 pub use v_str::VStr;
@@ -142,22 +146,48 @@ pub const fn no(_: &str) -> bool {
     false
 }
 
-pub fn get_vnode_call_helper(v: &VNodeIR<BaseConvertInfo>) -> RuntimeHelper {
-    use RuntimeHelper as RH;
-    if v.is_block {
-        return if v.is_component {
-            RH::CREATE_BLOCK
-        } else {
-            RH::CREATE_ELEMENT_BLOCK
-        };
+/// How a vnode is created at runtime. Blocks track dynamic descendants,
+/// components are created by createVNode family instead of element ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VNodeCallKind {
+    /// createElementVNode
+    Element,
+    /// createElementBlock
+    ElementBlock,
+    /// createVNode
+    Component,
+    /// createBlock
+    ComponentBlock,
+}
+
+impl VNodeCallKind {
+    pub fn new(is_block: bool, is_component: bool) -> Self {
+        match (is_block, is_component) {
+            (false, false) => Self::Element,
+            (true, false) => Self::ElementBlock,
+            (false, true) => Self::Component,
+            (true, true) => Self::ComponentBlock,
+        }
     }
-    if v.is_component {
-        RH::CREATE_VNODE
-    } else {
-        RH::CREATE_ELEMENT_VNODE
+    pub fn helper(self) -> RuntimeHelper {
+        use RuntimeHelper as RH;
+        match self {
+            Self::Element => RH::CREATE_ELEMENT_VNODE,
+            Self::ElementBlock => RH::CREATE_ELEMENT_BLOCK,
+            Self::Component => RH::CREATE_VNODE,
+            Self::ComponentBlock => RH::CREATE_BLOCK,
+        }
     }
 }
 
+#[cfg(feature = "std")]
+/// is_component mirrors the ElementType resolved by parser, so custom
+/// elements recognized by is_custom_element use element helpers.
+pub fn get_vnode_call_helper(v: &VNodeIR<BaseConvertInfo>) -> RuntimeHelper {
+    VNodeCallKind::new(v.is_block, v.is_component).helper()
+}
+
+#[cfg(feature = "std")]
 pub fn is_builtin_symbol(tag: &Js, helper: RuntimeHelper) -> bool {
     if let Js::Symbol(r) = tag {
         r == &helper
@@ -472,6 +502,21 @@ mod test {
     use super::*;
     use crate::parser::test::mock_element;
 
+    #[test]
+    fn test_vnode_call_kind() {
+        use RuntimeHelper as RH;
+        let table = [
+            (false, false, RH::CREATE_ELEMENT_VNODE),
+            (true, false, RH::CREATE_ELEMENT_BLOCK),
+            (false, true, RH::CREATE_VNODE),
+            (true, true, RH::CREATE_BLOCK),
+        ];
+        for (is_block, is_component, helper) in table {
+            let kind = VNodeCallKind::new(is_block, is_component);
+            assert!(kind.helper() == helper, "{:?}", kind);
+        }
+    }
+
     #[test]
     fn test_find_dir() {
         let e = mock_element("<p v-if=true/>");
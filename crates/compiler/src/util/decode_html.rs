@@ -1,11 +1,9 @@
 // sadly current html decode crate requires std::io::Write not fmt
-use std::fmt::{self, Write};
+use core::fmt::{self, Write};
 use super::named_chars::NAMED_CHAR_REF;
-use lazy_static::lazy_static;
 
-lazy_static! {
-    static ref MAX_CR_NAME_LEN: usize = NAMED_CHAR_REF.keys().copied().map(str::len).max().unwrap();
-}
+// length of the longest key in NAMED_CHAR_REF: CounterClockwiseContourIntegral;
+const MAX_CR_NAME_LEN: usize = 32;
 
 type DecodeResult<'a> = Result<&'a str, fmt::Error>;
 pub fn decode_entities<W: Write>(s: &str, mut w: W, as_attr: bool) -> fmt::Result {
@@ -33,6 +31,7 @@ fn decode_named_ref<W: Write>(s: &str, mut w: W, as_attr: bool) -> DecodeResult
     let max_len = MAX_CR_NAME_LEN.min(src.len());
     let entry = (2..=max_len)
         .rev()
+        .filter(|&i| src.is_char_boundary(i))
         .map(|i| &src[..i])
         .find_map(|k| NAMED_CHAR_REF.get_entry(k));
     let (key, val) = match entry {
@@ -66,12 +65,15 @@ fn decode_numeric_ref<W: Write>(s: &str, mut w: W) -> DecodeResult {
                     (n, &src[cnt..])
                 }
             }
-            Err(_) => return Ok(src),
+            Err(_) => {
+                w.write_str("&#x")?;
+                return Ok(src);
+            }
         }
     } else {
         // num
         let src = &s[2..];
-        let cnt = src.chars().take_while(|c| c.is_numeric()).count();
+        let cnt = src.chars().take_while(|c| c.is_ascii_digit()).count();
         match src[..cnt].parse() {
             Ok(n) => {
                 if src[cnt..].starts_with(';') {
@@ -80,7 +82,10 @@ fn decode_numeric_ref<W: Write>(s: &str, mut w: W) -> DecodeResult {
                     (n, &src[cnt..])
                 }
             }
-            Err(_) => return Ok(src),
+            Err(_) => {
+                w.write_str("&#")?;
+                return Ok(src);
+            }
         }
     };
     let num = match num {
@@ -148,6 +153,9 @@ mod test {
             ("hej&#x3B;&#x20;hå", "hej; hå"),
             ("&quot;width&#x3A;&#32;3px&#59;&quot;", "\"width: 3px;\""),
             ("&#x2b;", "+"),
+            ("&a中文字符", "&a中文字符"),
+            ("&#١٢;", "&#١٢;"),
+            ("&#x;&#;", "&#x;&#;"),
         ];
         for &(input, expected) in data.iter() {
             let mut actual = String::new();
@@ -155,4 +163,9 @@ mod test {
             assert_eq!(&actual, expected);
         }
     }
+    #[test]
+    fn test_max_name_len() {
+        let max = NAMED_CHAR_REF.keys().copied().map(str::len).max();
+        assert_eq!(max, Some(MAX_CR_NAME_LEN));
+    }
 }
@@ -1,6 +1,6 @@
 /// I am sorry but json.rs requires io::Write
 /// but I can only present fmt::Write
-use std::fmt::{Result as Ret, Write};
+use core::fmt::{Result as Ret, Write};
 
 const QU: char = '"';
 const BS: char = '\\';
@@ -298,6 +298,43 @@ where
     })
 }
 
+/// Count non-trivia tokens in statements, None if the text is invalid.
+pub fn count_stmt_tokens(text: &str) -> Option<usize> {
+    let parsed = rl::parse_text_lossy(text, 0);
+    if !parsed.errors().is_empty() {
+        return None;
+    }
+    let count = parsed
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(|e| e.into_token())
+        .filter(|t| t.kind() != SyntaxKind::EOF)
+        .count();
+    Some(count)
+}
+
+const CONSTANT_KINDS: &[SyntaxKind] = &[
+    SyntaxKind::ARRAY_EXPR,
+    SyntaxKind::OBJECT_EXPR,
+    SyntaxKind::LITERAL_PROP,
+    SyntaxKind::NAME,
+    SyntaxKind::LITERAL,
+    SyntaxKind::GROUPING_EXPR,
+    SyntaxKind::UNARY_EXPR,
+];
+
+/// Object or array literal built only from constants, e.g. `{color: 'red'}`.
+/// Evaluating it has no side effect nor reads any variable.
+pub fn is_constant_collection(text: &str) -> bool {
+    let expr = match parse_js_expr(text) {
+        Some(Expr::ArrayExpr(e)) => e.syntax().clone(),
+        Some(Expr::ObjectExpr(e)) => e.syntax().clone(),
+        _ => return false,
+    };
+    expr.descendants()
+        .all(|n| CONSTANT_KINDS.contains(&n.kind()))
+}
+
 pub fn is_member_expression(text: &str) -> bool {
     is_member_expr_impl(parse_js_expr(text))
 }
@@ -432,6 +469,18 @@ mod test {
         ret
     }
 
+    #[test]
+    fn test_constant_collection() {
+        assert!(is_constant_collection("{color: 'red', 'font-size': -1}"));
+        assert!(is_constant_collection("[1, ['a'], {b: null}]"));
+        assert!(!is_constant_collection("'red'"));
+        assert!(!is_constant_collection("{color: c}"));
+        assert!(!is_constant_collection("{c}"));
+        assert!(!is_constant_collection("[rand()]"));
+        assert!(!is_constant_collection("{[k]: 1}"));
+        assert!(!is_constant_collection("[...a]"));
+    }
+
     #[test]
     fn test_walk_fn_param() {
         let cases = [
@@ -8,7 +8,8 @@ use super::{
     decode_html::decode_entities,
 };
 use bitflags::bitflags;
-use std::{
+use alloc::string::String;
+use core::{
     fmt::{self, Write},
     ops::Deref,
 };
@@ -19,33 +20,36 @@ bitflags! {
     /// depending on whether the manipulation is idempotent or not
     /// NB strops is order sensitive when it is cast to string.
     #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
-    pub struct StrOps: u16 {
-        const HANDLER_KEY         = 1 << 0;
-        const MODEL_HANDLER       = 1 << 1;
-        const VALID_DIR           = 1 << 2;
-        const VALID_COMP          = 1 << 3;
-        const V_DIR_PREFIX        = 1 << 4;
-        const COMPRESS_WHITESPACE = 1 << 5;
-        const DECODE_ENTITY       = 1 << 6;
-        const CAMEL_CASE          = 1 << 7;
-        const CAPITALIZED         = 1 << 8;
-        const JS_STRING           = 1 << 9;
-        const CTX_PREFIX          = 1 << 11;
-        const MOD_SUFFIX          = 1 << 12;
-        const ASSIGN_EVT          = 1 << 13;
+    pub struct StrOps: u32 {
+        const LOWER_CASE          = 1 << 0;
+        const HANDLER_KEY         = 1 << 1;
+        const MODEL_HANDLER       = 1 << 2;
+        const VALID_DIR           = 1 << 3;
+        const VALID_COMP          = 1 << 4;
+        const V_DIR_PREFIX        = 1 << 5;
+        const COMPRESS_WHITESPACE = 1 << 6;
+        const DECODE_ENTITY       = 1 << 7;
+        const CAMEL_CASE          = 1 << 8;
+        const CAPITALIZED         = 1 << 9;
+        const PROP_PREFIX         = 1 << 10;
+        const JS_STRING           = 1 << 11;
+        const CTX_PREFIX          = 1 << 12;
+        const MOD_SUFFIX          = 1 << 13;
+        const ASSIGN_EVT          = 1 << 14;
         // marker op is placed at the end
-        const SELF_SUFFIX         = 1 << 14;
-        const DECODE_ATTR         = 1 << 15;
+        const SELF_SUFFIX         = 1 << 15;
+        const DECODE_ATTR         = 1 << 16;
         /// Ops that can be safely carried out multiple times
         const IDEMPOTENT_OPS =
             Self::COMPRESS_WHITESPACE.bits() | Self::DECODE_ENTITY.bits() |
-            Self::CAMEL_CASE.bits() | Self::CAPITALIZED.bits() | Self::DECODE_ATTR.bits();
+            Self::CAMEL_CASE.bits() | Self::CAPITALIZED.bits() | Self::DECODE_ATTR.bits() |
+            Self::LOWER_CASE.bits();
         /// Ops that can only be performed at most once. Name comes from
         /// https://en.wikipedia.org/wiki/Substructural_type_system
         const AFFINE_OPS =
             Self::HANDLER_KEY.bits() | Self::MODEL_HANDLER.bits() | Self::VALID_DIR.bits() |
             Self::VALID_COMP.bits() | Self::SELF_SUFFIX.bits() | Self::V_DIR_PREFIX.bits() |
-            Self::JS_STRING.bits() | Self::CTX_PREFIX.bits();
+            Self::JS_STRING.bits() | Self::CTX_PREFIX.bits() | Self::PROP_PREFIX.bits();
         /// Ops that mark the string is an hoisted asset
         const ASSET_OPS = Self::VALID_DIR.bits() | Self::VALID_COMP.bits() |
             Self::SELF_SUFFIX.bits();
@@ -103,6 +107,14 @@ fn write_hyphenated<W: Write>(s: &str, mut w: W) -> fmt::Result {
     Ok(())
 }
 
+// HTML tokenizer only lowercases ASCII upper alpha in attribute names.
+fn write_lowercased<W: Write>(s: &str, mut w: W) -> fmt::Result {
+    for c in s.chars() {
+        w.write_char(c.to_ascii_lowercase())?;
+    }
+    Ok(())
+}
+
 /// compress consecutive whitespaces into one.
 fn write_compressed<W: Write>(mut s: &str, mut w: W) -> fmt::Result {
     while let Some(p) = s.find(|c: char| c.is_ascii_whitespace()) {
@@ -168,7 +180,7 @@ impl StrOps {
         let mut dest = String::new();
         for op in self.iter() {
             Self::write_one_op(op, src, &mut dest)?;
-            std::mem::swap(&mut temp, &mut dest);
+            core::mem::swap(&mut temp, &mut dest);
             dest.clear();
             src = &temp;
         }
@@ -181,6 +193,7 @@ impl StrOps {
             StrOps::DECODE_ENTITY => write_decoded(s, w),
             StrOps::DECODE_ATTR => write_attr_decoded(s, w),
             StrOps::JS_STRING => write_json_string(s, w),
+            StrOps::LOWER_CASE => write_lowercased(s, w),
             StrOps::CAMEL_CASE => write_camelized(s, w),
             StrOps::CAPITALIZED => write_capitalized(s, w),
             StrOps::VALID_DIR => write_valid_asset(s, w, "directive"),
@@ -193,6 +206,10 @@ impl StrOps {
                 w.write_str("v-")?;
                 w.write_str(s)
             }
+            StrOps::PROP_PREFIX => {
+                w.write_str(".")?;
+                w.write_str(s)
+            }
             StrOps::HANDLER_KEY => {
                 w.write_str("on")?;
                 let len = s.chars().next().unwrap().len_utf8();
@@ -246,6 +263,9 @@ impl<'a> VStr<'a> {
         }
         is_event_prop(s.raw)
     }
+    pub fn is_model_handler(s: &VStr) -> bool {
+        s.ops.contains(StrOps::MODEL_HANDLER) || s.raw.starts_with("onUpdate:")
+    }
     pub fn is_self_suffixed(s: &VStr) -> bool {
         s.ops.contains(StrOps::SELF_SUFFIX)
     }
@@ -255,6 +275,12 @@ impl<'a> VStr<'a> {
     pub fn is_ctx_prefixed(s: &VStr) -> bool {
         s.ops.contains(StrOps::CTX_PREFIX)
     }
+    pub fn is_camelized(s: &VStr) -> bool {
+        s.ops.contains(StrOps::CAMEL_CASE)
+    }
+    pub fn is_prop_key(s: &VStr) -> bool {
+        s.ops.contains(StrOps::PROP_PREFIX)
+    }
     pub fn is_event_assign(s: &VStr) -> bool {
         s.ops.contains(StrOps::ASSIGN_EVT)
     }
@@ -264,6 +290,7 @@ impl<'a> VStr<'a> {
                 | StrOps::VALID_DIR
                 | StrOps::VALID_COMP
                 | StrOps::V_DIR_PREFIX
+                | StrOps::PROP_PREFIX
                 | StrOps::CTX_PREFIX
                 | StrOps::MOD_SUFFIX
                 | StrOps::ASSIGN_EVT,
@@ -289,6 +316,10 @@ impl<'a> VStr<'a> {
         self.ops |= StrOps::CAPITALIZED;
         self
     }
+    pub fn lowercase(&mut self) -> &mut Self {
+        self.ops |= StrOps::LOWER_CASE;
+        self
+    }
     pub fn pascalize(&mut self) -> &mut Self {
         self.camelize().capitalize()
     }
@@ -333,6 +364,11 @@ impl<'a> VStr<'a> {
         self.ops |= StrOps::V_DIR_PREFIX;
         self
     }
+    /// force binding as DOM property: value -> .value
+    pub fn prefix_prop(&mut self) -> &mut Self {
+        self.ops |= StrOps::PROP_PREFIX;
+        self
+    }
     pub fn be_js_str(&mut self) -> &mut Self {
         self.ops |= StrOps::JS_STRING;
         self
@@ -440,6 +476,8 @@ mod test {
             ("å—åŒ–ã‘", StrOps::empty(), "å—åŒ–ã‘"),
             ("å—åŒ–ã‘", StrOps::JS_STRING, stringify!("å—åŒ–ã‘")),
             ("foo-bar", StrOps::CAMEL_CASE, "fooBar"),
+            ("myProp", StrOps::LOWER_CASE, "myprop"),
+            ("ÄbC", StrOps::LOWER_CASE, "Äbc"),
             ("foo-bar", StrOps::CAPITALIZED, "Foo-bar"),
             ("", StrOps::CAPITALIZED, ""),
             ("ālaya-vijñāna", StrOps::CAMEL_CASE, "ālayaVijñāna"),
@@ -1,24 +1,64 @@
 use vue_compiler_core as compiler;
-use super::common::get_compiler;
-use compiler::compiler::TemplateCompiler;
+use super::common::{compile_with, compile_with_info, get_compile_option, get_compiler};
+use compiler::codegen::ScriptMode;
+use compiler::compiler::{CompileOption, TemplateCompiler};
+use compiler::SFCInfo;
 use crate::meta_macro;
-use rslint_parser::parse_text;
+use rslint_parser::{parse_module, parse_text};
 
 fn assert_codegen(case: &str) -> String {
-    let val = base_compile(case);
-    // `function target have return outside function
-    let wrap_in_func = format!("function () {{ {} }}", val);
-    let parsed = parse_text(&wrap_in_func, 0);
-    assert!(parsed.errors().is_empty());
-    val
+    codegen_with(case, get_compile_option(), &SFCInfo::default())
 }
 meta_macro!(assert_codegen);
 
-pub fn base_compile(source: &str) -> String {
-    let sfc_info = Default::default();
-    let compiler = get_compiler();
-    let ret = compiler.compile(source, &sfc_info).unwrap();
-    String::from_utf8(ret).unwrap()
+fn prefix_codegen(case: &str) -> String {
+    codegen_with(case, prefix_option(), &SFCInfo::default())
+}
+meta_macro!(prefix_codegen);
+
+// snapshot cases compiled with a custom option and optional SFCInfo
+macro_rules! assert_codegen_with {
+    ($cases: expr, $option: expr) => {
+        assert_codegen_with!($cases, $option, &SFCInfo::default())
+    };
+    ($cases: expr, $option: expr, $info: expr) => {
+        for case in $cases {
+            crate::assert_yaml!(case, |c| codegen_with(c, $option, $info));
+        }
+    };
+}
+
+fn codegen_with<'a>(case: &'a str, option: CompileOption, info: &'a SFCInfo<'a>) -> String {
+    let is_module = matches!(option.mode, ScriptMode::Module { .. });
+    let val = compile_with_info(case, option, info);
+    let errors = if is_module {
+        parse_module(&val, 0).errors().len()
+    } else {
+        // `function target have return outside function
+        let wrap_in_func = format!("function () {{ {} }}", val);
+        parse_text(&wrap_in_func, 0).errors().len()
+    };
+    assert_eq!(errors, 0, "{}", val);
+    val
+}
+
+fn prefix_option() -> CompileOption {
+    CompileOption {
+        mode: ScriptMode::Function {
+            prefix_identifier: true,
+            runtime_global_name: "Vue".into(),
+        },
+        ..get_compile_option()
+    }
+}
+
+fn module_option() -> CompileOption {
+    CompileOption {
+        mode: ScriptMode::Module {
+            runtime_module_name: "vue".into(),
+        },
+        ..get_compile_option()
+    }
 }
 
 #[test]
@@ -30,3 +70,448 @@ fn test_text_codegen() {
         "<comp>Hello {{world}}</comp>",
     ]];
 }
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_ir_json() {
+    let option = CompileOption {
+        emit_ir_json: true,
+        ..get_compile_option()
+    };
+    let ret = compile_with("<p :id='a'>{{b}}</p>", option);
+    let json: serde_json::Value = serde_json::from_str(&ret).unwrap();
+    let vnode = &json["body"][0]["VNodeCall"];
+    assert_eq!(vnode["tag"]["StrLit"], "p");
+    assert_eq!(vnode["props"]["Props"][0][0]["StrLit"], "id");
+    assert_eq!(vnode["is_component"], false);
+    assert!(vnode["patch_flag"].as_str().unwrap().contains("PROPS"));
+    assert_eq!(vnode["loc"]["start"], "Pos: 0, Ln: 1, Col: 1");
+    assert!(vnode["children"][0]["TextCall"].is_object());
+}
+
+#[test]
+fn test_interpolation_display_string() {
+    prefix_codegen![["<p>{{count}}</p>", "<p>a {{ 'x' }}</p>"]];
+}
+
+#[test]
+fn test_v_for_range_and_destructure() {
+    prefix_codegen![[
+        "<p v-for='n in 10'>{{n}}</p>",
+        "<p v-for='{ a, b } in list'>{{a + c}}</p>",
+    ]];
+}
+
+#[test]
+fn test_v_for_object_three_aliases() {
+    prefix_codegen![[
+        "<p v-for='(value, name, index) in object'>{{value}}{{name}}{{index}}{{other}}</p>"
+    ]];
+}
+
+#[test]
+fn test_static_v_once_hoisted() {
+    assert_codegen![[
+        "<div><p v-once>static</p><p v-once>{{a}}</p></div>",
+        "<div><p v-once>static</p>{{a}}</div>",
+    ]];
+}
+
+#[test]
+fn test_hoist_pure_annotation() {
+    let case = "<div><p>static</p>{{a}}</div>";
+    assert_codegen![[case]];
+    let option = || CompileOption {
+        pure_annotation: true,
+        ..get_compile_option()
+    };
+    assert_codegen_with!([case], option());
+}
+
+#[test]
+fn test_deterministic_hoist_names() {
+    let case = "<div><p>a</p><span class='b'>{{c}}</span><i>d</i></div>";
+    assert_codegen![[case]];
+    let option = || CompileOption {
+        hoist_prefix: "_h".into(),
+        ..get_compile_option()
+    };
+    assert_codegen_with!([case], option());
+}
+
+#[test]
+fn test_hoist_static_props_only() {
+    assert_codegen![[r#"<div class="x" id="y"><span>{{d}}</span></div>"#]];
+}
+
+#[test]
+fn test_inline_mode() {
+    use compiler::{BindingMetadata, BindingTypes};
+    use rustc_hash::FxHashMap;
+    let mut map = FxHashMap::default();
+    map.insert("count", BindingTypes::SetupRef);
+    let sfc_info = SFCInfo {
+        inline: true,
+        binding_metadata: BindingMetadata::new_setup(map),
+        ..Default::default()
+    };
+    assert_codegen_with!(
+        ["<p>{{count}} {{other}}</p>"],
+        get_compile_option(),
+        &sfc_info
+    );
+}
+
+#[test]
+fn test_stable_prop_order() {
+    prefix_codegen![["<p v-if='a' id='x' :b='y' class='c' :class='d' title='t'/>"]];
+}
+
+#[test]
+fn test_alterable_slot() {
+    assert_codegen![[r#"<comp><template #a v-if="x">a</template></comp>"#]];
+}
+
+#[test]
+fn test_bare_template_as_fragment() {
+    assert_codegen![["<div><template><p/>{{a}}</template></div>"]];
+}
+
+#[test]
+fn test_v_bind_same_name_shorthand() {
+    assert_eq!(
+        compile_with("<div :id/>", prefix_option()),
+        compile_with("<div :id='id'/>", prefix_option())
+    );
+    prefix_codegen![["<div :foo-bar/>"]];
+}
+
+#[test]
+fn test_minify() {
+    let case = "<div v-for='i in list' :key='i'><pre>a  b</pre>{{ i }}</div><comp/>";
+    let option = |minify| CompileOption {
+        minify,
+        hoist_static: true,
+        ..get_compile_option()
+    };
+    assert_codegen_with!([case], option(true));
+    let pretty = compile_with(case, option(false));
+    let minified = compile_with(case, option(true));
+    let strip = |s: &str| s.replace(|c: char| c.is_whitespace() || c == ';', "");
+    assert_eq!(strip(&pretty), strip(&minified));
+}
+
+#[test]
+fn test_component_resolver() {
+    use compiler::converter::ComponentResolution;
+    let option = || CompileOption {
+        component_resolver: Some(|tag| match tag {
+            "MyComp" => ComponentResolution::Identifier("MyComp"),
+            t if t.starts_with("Lazy") => ComponentResolution::Expression(format!(
+                "defineAsyncComponent(() => import('./{}.vue'))",
+                t
+            )),
+            _ => ComponentResolution::Runtime,
+        }),
+        ..get_compile_option()
+    };
+    assert_codegen_with!(
+        ["<div><MyComp/><LazyA/><LazyB/><LazyA/><comp/></div>"],
+        option()
+    );
+}
+
+#[test]
+fn test_hydrate_events() {
+    use compiler::converter::v_on::V_ON;
+    let option = || {
+        let mut option = CompileOption {
+            hoist_static: true,
+            ..get_compile_option()
+        };
+        option.directive_converters.insert(V_ON.0, V_ON.1);
+        option
+    };
+    assert_codegen_with!(
+        [
+            r#"<button @click="f">a</button>"#,
+            r#"<button :id="f">a</button>"#,
+            r#"<comp @click="f"/>"#,
+        ],
+        option()
+    );
+}
+
+#[test]
+fn test_compile_stats() {
+    use compiler::compiler::{get_base_passes, BaseCompiler, CompileStats};
+    let option = CompileOption {
+        hoist_static: true,
+        ..get_compile_option()
+    };
+    let sfc_info = Default::default();
+    let compiler = BaseCompiler::new(Vec::new, get_base_passes, option);
+    let case = r#"<div><p>static</p><comp v-once/><span :id="x">{{y}}</span></div>"#;
+    let result = compiler.compile_with_stats(case, &sfc_info).unwrap();
+    let code = String::from_utf8(result.output).unwrap();
+    assert_eq!(
+        result.stats,
+        CompileStats {
+            // createVNode, createElementVNode, resolveComponent,
+            // toDisplayString and setBlockTracking
+            helpers: 5,
+            components: 1,
+            directives: 0,
+            // static <p> and dynamic props hint of <span>
+            hoists: 2,
+            cache_slots: 1,
+            patched_nodes: 1,
+        },
+        "{}",
+        code
+    );
+}
+
+#[test]
+fn test_root_cache_and_hoists() {
+    use compiler::converter::Hoist;
+    let compiler = get_compiler();
+    let sfc_info = Default::default();
+    let case = "<div><p v-once>{{a}}</p><p v-once>{{b}}</p><b>x</b></div>";
+    let ast = compiler.parse(compiler.scan(case));
+    let mut ir = compiler.convert(ast, &sfc_info);
+    compiler.transform(&mut ir, &sfc_info);
+    // cache slots and hoists are collected on the root's top scope
+    assert_eq!(ir.top_scope.cache_slots, 2);
+    assert_eq!(ir.top_scope.hoists.len(), 1);
+    assert!(matches!(ir.top_scope.hoists[0], Hoist::FullElement(_)));
+    assert_codegen![[case]];
+}
+
+#[test]
+fn test_slot_fn_with_ctx() {
+    prefix_codegen![[
+        r#"<comp><template #default="{ row }">{{ row.id }}</template></comp>"#,
+        r#"<comp #default="{ row = def, ...rest }">{{ rest }}</comp>"#,
+    ]];
+}
+
+#[test]
+fn test_html_mode_component_case() {
+    use compiler::parser::ParseMode;
+    let option = || CompileOption {
+        parse_mode: ParseMode::Html,
+        ..get_compile_option()
+    };
+    // end tag closes the component so text is its sibling
+    assert_codegen_with!(["<MyComponent></mycomponent>text"], option());
+}
+
+#[test]
+fn test_transform_asset_url() {
+    fn transform_asset_url<'a>(tag: &str, attr: &str, url: &'a str) -> Option<&'a str> {
+        if tag == "img" && attr == "src" && url.starts_with("./") {
+            Some(url)
+        } else {
+            None
+        }
+    }
+    let case = r#"<div><img src="./a.png"/><img src="./b.png"/><img src="./a.png"/><img src="https://c.png"/></div>"#;
+    let option = |base: CompileOption| CompileOption {
+        transform_asset_url: Some(transform_asset_url),
+        ..base
+    };
+    assert_codegen_with!([case], option(module_option()));
+    // function mode cannot import so urls are kept
+    assert_codegen_with!([case], option(get_compile_option()));
+}
+
+#[test]
+fn test_v_for_fragment_flag() {
+    prefix_codegen![[
+        "<p v-for='a in b' :key='a'/>",
+        "<p v-for='a in b'/>",
+        // constant source never changes length
+        "<p v-for='a in 10'/>",
+    ]];
+}
+
+#[test]
+fn test_force_fragment_root() {
+    let option = |force_fragment_root| CompileOption {
+        force_fragment_root,
+        ..get_compile_option()
+    };
+    assert_codegen_with!(["<p>{{a}}</p>"], option(false));
+    assert_codegen_with!(["<p>{{a}}</p>", "<p/><i/>", ""], option(true));
+}
+
+#[test]
+fn test_dynamic_component_spread_and_slot() {
+    prefix_codegen![[
+        r#"<component :is="comp" v-bind="attrs" id="a"><slot/></component>"#,
+        // sole spread is normalized instead of merged
+        r#"<component :is="comp" v-bind="attrs"><slot/></component>"#,
+    ]];
+}
+
+#[test]
+fn test_v_bind_prop_modifier() {
+    prefix_codegen![[
+        "<input :value.prop='v'/>",
+        "<input :text-content.prop.camel='v'/>",
+    ]];
+}
+
+#[test]
+fn test_prefix_identifier_no_with() {
+    prefix_codegen![["<p :id='a'>{{c}}<slot/></p>"]];
+    assert_codegen![["{{c}}"]];
+}
+
+#[test]
+fn test_v_if_comment_fallback() {
+    let option = |is_dev| CompileOption {
+        is_dev,
+        ..get_compile_option()
+    };
+    assert_codegen_with!(
+        [
+            "<p v-if='a'/>",
+            "<p v-if='a'/><p v-else/>",
+            "<p v-if='a'/><p v-else-if='b'/>",
+        ],
+        option(true)
+    );
+    assert_codegen_with!(["<p v-if='a'/>"], option(false));
+}
+
+#[test]
+fn test_scoped_slot_outlet_fallback() {
+    prefix_codegen![[r#"<slot :row="r">default</slot>"#, r#"<slot :row="r"/>"#]];
+}
+
+#[test]
+fn test_v_is_compat() {
+    let option = |compat_v_is| CompileOption {
+        compat_v_is,
+        ..get_compile_option()
+    };
+    assert_codegen_with!(["<tr v-is='row'/>"], option(true));
+    // without compat v-is is dropped and tr stays a plain element
+    assert_codegen_with!(["<tr v-is='row'/>"], option(false));
+}
+
+#[test]
+fn test_custom_element_attr_case() {
+    use compiler::parser::ParseMode;
+    use compiler::Namespace;
+    let option = |preserve_custom_element_attr_case| CompileOption {
+        parse_mode: ParseMode::Html,
+        is_custom_element: |s| s.contains('-'),
+        get_namespace: |tag, parent| match parent {
+            Some(p) if p.namespace == Namespace::Svg => Namespace::Svg,
+            _ if tag == "svg" => Namespace::Svg,
+            _ => Namespace::Html,
+        },
+        preserve_custom_element_attr_case,
+        ..get_compile_option()
+    };
+    let case = "<div><my-el myProp='x'></my-el><div dataId='y' :fooBar='z'></div></div>";
+    assert_codegen_with!([case], option(true));
+    // svg attributes keep their casing
+    let svg = "<svg viewBox='0 0 10 10'><path fillRule='a'/></svg>";
+    assert_codegen_with!([case, svg], option(false));
+}
+
+#[test]
+fn test_text_vnode_patch_flag() {
+    assert_codegen![[
+        "<div>{{x}}<span/></div>",
+        "<div>a<span/></div>",
+        // sole text child is passed as children directly
+        "<div>{{x}}</div>",
+    ]];
+}
+
+#[test]
+fn test_helper_prefix() {
+    let option = |base: CompileOption| CompileOption {
+        helper_prefix: "$vue_".into(),
+        ..base
+    };
+    let case = "<p v-if='a'>{{b}}</p><i v-for='c in d'/>";
+    assert_codegen_with!([case], option(get_compile_option()));
+    assert_codegen_with!([case], option(module_option()));
+}
+
+#[test]
+fn test_verbatim_attr_names() {
+    use compiler::parser::ParseMode;
+    assert_codegen![[
+        r#"<svg xmlns:xlink="x" data-foo-bar="a" aria-label="b" :data-baz-qux="c"/>"#
+    ]];
+    // Html mode lowercases all attribute names like the HTML tokenizer
+    let option = || CompileOption {
+        parse_mode: ParseMode::Html,
+        ..get_compile_option()
+    };
+    assert_codegen_with!(
+        [r#"<div data-fooBar="a" aria-describedBy="b" tabIndex="d"/>"#],
+        option()
+    );
+}
+
+#[test]
+fn test_camelize_component_bind() {
+    assert_codegen![["<comp :my-prop='a'/>", "<div :my-prop='a'/>"]];
+    // custom elements receive attributes, not component props
+    let option = || CompileOption {
+        is_native_tag: |s| s != "comp" && s != "my-el",
+        is_custom_element: |s| s == "my-el",
+        ..get_compile_option()
+    };
+    assert_codegen_with!(["<my-el :my-prop='a'/>"], option());
+}
+
+#[test]
+fn test_drop_empty_props() {
+    assert_codegen![[
+        "<div :class=\"''\" :style='null'>hi</div>",
+        // statically authored empty attributes are kept
+        "<div class=''>hi</div>",
+    ]];
+}
+
+#[test]
+fn test_option_binding_access() {
+    use compiler::{BindingMetadata, BindingTypes};
+    let mut map = rustc_hash::FxHashMap::default();
+    map.insert("p", BindingTypes::Props);
+    map.insert("d", BindingTypes::Data);
+    map.insert("o", BindingTypes::Options);
+    let sfc_info = SFCInfo {
+        binding_metadata: BindingMetadata::new_option(map),
+        ..Default::default()
+    };
+    assert_codegen_with!(
+        ["<p :id='p'>{{ d + o + u }}</p>"],
+        prefix_option(),
+        &sfc_info
+    );
+}
+
+#[test]
+fn test_scoped_class_merge() {
+    let option = || CompileOption {
+        hoist_static: true,
+        ..module_option()
+    };
+    let sfc_info = SFCInfo {
+        scope_id: Some("data-v-7ba5bd90".into()),
+        ..SFCInfo::default()
+    };
+    // scope id is applied by withScopeId, static and dynamic classes are kept
+    let case = "<div class='a' :class='b'><comp class='c' :class='d'/><p class='e'>x</p></div>";
+    assert_codegen_with!([case], option(), &sfc_info);
+}
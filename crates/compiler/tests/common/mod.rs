@@ -4,6 +4,7 @@ use compiler::error::VecErrorHandler;
 use compiler::transformer::CorePass;
 use compiler::compiler::TemplateCompiler;
 pub use compiler::{Position, SourceLocation};
+use compiler::SFCInfo;
 use compiler::compiler::{BaseCompiler, CompileOption, get_base_passes};
 use compiler::scanner::TextMode;
 use serde::Serialize;
@@ -27,7 +28,7 @@ fn get_text_mode(s: &str) -> TextMode {
     }
 }
 
-fn get_compile_option() -> CompileOption {
+pub fn get_compile_option() -> CompileOption {
     CompileOption {
         get_text_mode,
         is_native_tag: |s| s != "comp",
@@ -41,6 +42,21 @@ pub fn get_compiler<'a>() -> BaseCompiler<'a, impl CorePass<BaseConvertInfo<'a>>
     BaseCompiler::new(dest, get_base_passes, get_compile_option())
 }
 
+/// compile source with customized option, e.g. `..get_compile_option()`
+pub fn compile_with(source: &str, option: CompileOption) -> String {
+    compile_with_info(source, option, &SFCInfo::default())
+}
+
+pub fn compile_with_info<'a>(
+    source: &'a str,
+    option: CompileOption,
+    sfc_info: &'a SFCInfo<'a>,
+) -> String {
+    let compiler = BaseCompiler::new(Vec::new, get_base_passes, option);
+    let ret = compiler.compile(source, sfc_info).unwrap();
+    String::from_utf8(ret).unwrap()
+}
+
 #[derive(Serialize)]
 pub struct TestError {
     pub loc: SourceLocation,
@@ -41,3 +41,172 @@ fn test_abrupt_closing_of_comment() {
         r#"<template><!----></template>"#,
     ]];
 }
+
+#[test]
+fn test_filename_in_message() {
+    use super::common::compile_with;
+    use compiler::compiler::CompileOption;
+    use compiler::error::VecErrorHandler;
+    use std::rc::Rc;
+    use vue_compiler_core as compiler;
+    let error_handler = Rc::new(VecErrorHandler::new());
+    let option = CompileOption {
+        filename: Some("App.vue".into()),
+        error_handler: error_handler.clone(),
+        ..Default::default()
+    };
+    compile_with("<div>", option);
+    let errors = error_handler.errors();
+    assert!(!errors.is_empty());
+    let msg = errors[0].to_string();
+    assert!(msg.starts_with("App.vue: "), "{}", msg);
+    assert!(msg.ends_with(errors[0].msg()), "{}", msg);
+}
+
+#[test]
+fn test_orphan_template_else() {
+    let dangling = "v-else/v-else-if has no adjacent v-if.";
+    for case in [
+        "<template v-else><p/></template>",
+        "<div/><template v-else-if='a'><p/><p/></template>",
+        "<!--c--><p v-else/>",
+    ] {
+        let errors = get_errors(case);
+        assert_eq!(errors.len(), 1, "{}", case);
+        assert_eq!(errors[0].msg, dangling);
+    }
+    let errors = get_errors("<p v-if='a'/><template v-else><p/></template>");
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn test_v_for_malformed_alias() {
+    let errors = get_errors("<p v-for='a b in list'/>");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].msg, "v-for has invalid expression.");
+}
+
+#[test]
+fn test_nesting_too_deep() {
+    let depth = 2000;
+    let case = format!(
+        "{}{{{{a}}}}{}",
+        "<div>".repeat(depth),
+        "</div>".repeat(depth)
+    );
+    // elements kept by default must compile on a default 2 MiB stack
+    let errors = std::thread::spawn(move || get_errors(&case))
+        .join()
+        .unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].msg,
+        "Template nesting is too deep. Deeper elements are ignored."
+    );
+}
+
+#[test]
+fn test_bare_template_warning() {
+    let errors = get_errors("<div><template><p/>{{a}}</template></div>");
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].msg.starts_with("<template> without v-if"));
+    assert!(get_errors("<template v-if='a'><p/></template>").is_empty());
+    assert!(get_errors("<template v-for='a in b'><p/></template>").is_empty());
+}
+
+#[test]
+fn test_v_bind_shorthand_no_error() {
+    assert!(get_errors("<div :id/>").is_empty());
+    let errors = get_errors("<div :id=''/>");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].msg, "v-bind is missing expression.");
+}
+
+#[test]
+fn test_unquoted_attr_value_invalid_char() {
+    assert!(get_errors("<div id=foo></div>").is_empty());
+    assert!(get_errors("<div id='foo'></div>").is_empty());
+    for case in ["<div id=a<b></div>", "<div id=a=b></div>"] {
+        let errors = get_errors(case);
+        assert_eq!(errors.len(), 1, "{}", case);
+        assert_eq!(
+            errors[0].msg,
+            "Unquoted attribute value cannot contain U+0022 (\"), U+0027 ('), U+003C (<), U+003D (=), and U+0060 (`)."
+        );
+    }
+}
+
+#[test]
+fn test_v_slot_misplaced() {
+    let errors = get_errors("<div v-slot='{ a }'>{{a}}</div>");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].msg,
+        "v-slot can only be used on components or <template> tags."
+    );
+    assert!(get_errors("<comp v-slot='{ a }'>{{a}}</comp>").is_empty());
+    assert!(get_errors("<comp><template #foo='{ a }'>{{a}}</template></comp>").is_empty());
+}
+
+#[test]
+fn test_interpolation_in_attribute() {
+    let errors = get_errors(r#"<div id="{{ x }}"></div>"#);
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].msg.starts_with("Interpolation inside attributes"));
+    assert_eq!(errors[0].loc.start.offset, 8);
+    assert!(get_errors(r#"<div :id="x"></div>"#).is_empty());
+    assert!(get_errors(r#"<div v-pre id="{{ x }}"></div>"#).is_empty());
+}
+
+#[test]
+fn test_v_for_malformed_expression() {
+    assert!(get_errors("<p v-for='(item, index) in list'/>").is_empty());
+    assert!(get_errors("<p v-for='{ a, b } of list'/>").is_empty());
+    for case in [
+        "<p v-for='item list'/>",
+        "<p v-for='item in list list'/>",
+        "<p v-for='(a b) in list'/>",
+        "<p v-for='(value, key, index in object'/>",
+    ] {
+        let errors = get_errors(case);
+        assert_eq!(errors.len(), 1, "{}", case);
+        assert_eq!(errors[0].msg, "v-for has invalid expression.");
+    }
+}
+
+#[test]
+fn test_v_is_removed() {
+    let errors = get_errors("<tr v-is='row'/>");
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].msg.starts_with("v-is has been removed."));
+    assert!(get_errors("<tr is='vue:row'/>").is_empty());
+    assert!(get_errors("<component :is='row'/>").is_empty());
+}
+
+#[test]
+fn test_forbidden_helper() {
+    use compiler::compiler::{BaseCompiler, CompileOption, TemplateCompiler, get_base_passes};
+    use compiler::error::VecErrorHandler;
+    use compiler::flags::RuntimeHelper as RH;
+    use std::rc::Rc;
+    use vue_compiler_core as compiler;
+    let error_handler = Rc::new(VecErrorHandler::new());
+    let option = CompileOption {
+        deny_helpers: [RH::SUSPENSE].iter().copied().collect(),
+        error_handler: error_handler.clone(),
+        ..Default::default()
+    };
+    let sfc_info = Default::default();
+    let compiler = BaseCompiler::new(Vec::new, get_base_passes, option);
+    let _ = compiler.compile("<div/>", &sfc_info).unwrap();
+    assert!(error_handler.errors().is_empty());
+    let _ = compiler
+        .compile("<Suspense><p/></Suspense>", &sfc_info)
+        .unwrap();
+    let errors = error_handler.errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].to_string(),
+        "Template uses a runtime helper in the deny list: Suspense"
+    );
+}
@@ -0,0 +1,132 @@
+use super::base_parse;
+use super::super::common::get_compiler;
+use vue_compiler_core::compiler::TemplateCompiler;
+
+fn assert_no_panic(s: &str) {
+    base_parse(s);
+    get_compiler()
+        .compile(s, &Default::default())
+        .expect("compile should not fail");
+}
+
+#[test]
+fn test_malformed_input() {
+    let cases = [
+        "<",
+        "</",
+        "<p",
+        "<p id",
+        "<p id=",
+        r#"<p id="a"#,
+        "<p id='a",
+        "{{",
+        "{{ a",
+        "{{ a }",
+        "<p v-if=",
+        "<p :[",
+        "<p :[中",
+        "<p :[中].a",
+        "<!--",
+        "<![CDATA[",
+        "<?",
+        "&",
+        "&#",
+        "&#x",
+        "&a中",
+        "&#١٢;",
+        "a</x>b",
+        "<textarea>a</textare中",
+        "<script>中</scri😀",
+        "<div v-for=",
+        "<div v-for='(a, in b'>",
+        "<template #",
+    ];
+    for case in cases {
+        assert_no_panic(case);
+    }
+}
+
+// characters chosen to hit tokenizer state transitions and multibyte boundaries.
+const PIECES: &[&str] = &[
+    "<",
+    ">",
+    "/",
+    "</",
+    "/>",
+    "=",
+    "\"",
+    "'",
+    "`",
+    "{{",
+    "}}",
+    "<!--",
+    "-->",
+    "<!",
+    "<?",
+    "<![CDATA[",
+    "]]>",
+    "&",
+    "&amp;",
+    "&#",
+    "&#x",
+    ";",
+    " ",
+    "\n",
+    "\t",
+    "\0",
+    "p",
+    "div",
+    "a",
+    "v-if",
+    "v-for",
+    "v-else",
+    "v-slot",
+    "v-bind",
+    ":",
+    "@",
+    "#",
+    ".",
+    "[",
+    "]",
+    "(",
+    ")",
+    "in",
+    "é",
+    "中",
+    "😀",
+    "\u{200b}",
+    "template",
+    "script",
+    "textarea",
+    "comp",
+    "slot",
+    "-",
+];
+
+// a tiny deterministic rng so failures are reproducible without extra deps.
+struct XorShift(u64);
+impl XorShift {
+    fn next(&mut self) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 as usize
+    }
+}
+
+fn random_template(rng: &mut XorShift) -> String {
+    let len = rng.next() % 60;
+    (0..len)
+        .map(|_| PIECES[rng.next() % PIECES.len()])
+        .collect()
+}
+
+#[test]
+fn test_random_input() {
+    let mut rng = XorShift(0x2545_f491_4f6c_dd1d);
+    for _ in 0..3000 {
+        let s = random_template(&mut rng);
+        let ret = std::panic::catch_unwind(|| assert_no_panic(&s));
+        assert!(ret.is_ok(), "panicked on input: {:?}", s);
+    }
+}
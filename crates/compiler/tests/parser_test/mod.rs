@@ -1,5 +1,6 @@
 use vue_compiler_core as compiler;
 mod dir;
+mod malformed;
 use super::common::{serialize_yaml, get_compiler};
 use compiler::compiler::TemplateCompiler;
 use compiler::parser::AstRoot;
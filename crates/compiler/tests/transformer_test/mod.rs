@@ -1 +1,40 @@
+use super::common::{get_compile_option, get_compiler};
+use compiler::compiler::{get_base_passes, BaseCompiler, CompileOption};
+use vue_compiler_core as compiler;
 
+#[test]
+fn test_helper_imports() {
+    let sfc_info = Default::default();
+    let compiler = get_compiler();
+    let case = "<p v-if='a'>{{b}}</p>";
+    let imports = compiler
+        .compile_with_stats(case, &sfc_info)
+        .unwrap()
+        .imports;
+    let expected = [
+        "openBlock",
+        "createElementBlock",
+        "createCommentVNode",
+        "toDisplayString",
+    ];
+    assert_eq!(imports.len(), expected.len());
+    for name in expected {
+        let alias = format!("_{}", name);
+        assert!(imports.contains(&(name, alias)), "{:?}", imports);
+    }
+}
+
+#[test]
+fn test_helper_imports_prefix() {
+    let sfc_info = Default::default();
+    let option = CompileOption {
+        helper_prefix: "$".into(),
+        ..get_compile_option()
+    };
+    let compiler = BaseCompiler::new(Vec::new, get_base_passes, option);
+    let imports = compiler
+        .compile_with_stats("{{a}}", &sfc_info)
+        .unwrap()
+        .imports;
+    assert_eq!(imports, [("toDisplayString", "$toDisplayString".into())]);
+}
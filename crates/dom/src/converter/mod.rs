@@ -6,11 +6,14 @@ mod v_text;
 
 use compiler::converter::{
     CoreDirConvRet, Directive, DirectiveConvertResult, DirectiveConverter, Element, ErrorHandler,
+    V_BIND, V_IS,
 };
 use compiler::ir::JsExpr;
 
 pub const DOM_DIR_CONVERTERS: &[DirectiveConverter] = &[
+    V_BIND,
     v_html::V_HTML,
+    V_IS,
     v_model::V_MODEL,
     v_on::V_ON,
     v_show::V_SHOW,
@@ -3,6 +3,7 @@ use compiler::converter::{CompilationError, v_model::convert_v_model_event};
 use compiler::flags::RuntimeHelper;
 use compiler::ir::JsExpr as Js;
 use compiler::parser::{ElemProp, DirectiveArg};
+use compiler::scanner::AttributeValue;
 use compiler::util::find_prop;
 use super::DirectiveConvertResult;
 use crate::extension::{dom_helper as dh, DomError};
@@ -22,6 +23,11 @@ pub fn convert_v_model<'a>(
             .with_location(dir.location.clone());
         eh.on_error(error);
     }
+    // runtime directive also receives the model as `binding.value`
+    let exp = dir.expression.as_ref().map(|v| AttributeValue {
+        content: v.content,
+        location: v.location.clone(),
+    });
     let mut base = convert_v_model_event(dir, e, eh);
     let (value, runtime) = match &mut base {
         DirectiveConvertResult::Dropped | DirectiveConvertResult::Preserve => return base,
@@ -33,6 +39,7 @@ pub fn convert_v_model<'a>(
                 check_redundant_value_prop(e, eh);
             }
             *runtime = Ok(rt);
+            dir.expression = exp;
         }
         Err(error) => eh.on_error(error),
     }
@@ -85,7 +92,11 @@ fn compute_v_model_runtime(e: &Element, dir: &Directive) -> RuntimeResult {
     let ty = ty.get_ref();
     let val = match ty {
         ElemProp::Dir(..) => return Ok(dh::V_MODEL_DYNAMIC),
-        ElemProp::Attr(attr) => attr.value.as_ref().expect("non empty"),
+        ElemProp::Attr(attr) => match &attr.value {
+            Some(val) => val,
+            // <input type v-model> is a text input
+            None => return Ok(dh::V_MODEL_TEXT),
+        },
     };
     match val.content.raw {
         "radio" => Ok(dh::V_MODEL_RADIO),
@@ -110,8 +121,117 @@ pub fn has_dynamic_v_bind(e: &Element) -> bool {
         .any(|d| {
             d.argument
                 .as_ref()
-                .map_or(true, |arg| matches!(arg, DirectiveArg::Dynamic(..)))
+                .is_none_or(|arg| matches!(arg, DirectiveArg::Dynamic(..)))
         })
 }
 
 pub const V_MODEL: DirectiveConverter = ("model", convert_v_model);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use compiler::error::{NoopErrorHandler, VecErrorHandler};
+    use compiler::parser::{AstNode, ParseOption, Parser};
+    use compiler::scanner::{ScanOption, Scanner};
+    use std::rc::Rc;
+
+    fn parse_model(s: &str) -> (Element<'_>, Directive<'_>) {
+        let eh = Rc::new(NoopErrorHandler);
+        let tokens = Scanner::new(ScanOption::default()).scan(s, eh.clone());
+        let parser = Parser::new(ParseOption {
+            is_native_element: is_native_tag,
+            is_custom_element: |s| s.contains('-'),
+            ..Default::default()
+        });
+        let mut elem = match parser.parse(tokens, eh).children.pop() {
+            Some(AstNode::Element(e)) => e,
+            _ => panic!("should be element"),
+        };
+        let dir = match elem.properties.pop() {
+            Some(ElemProp::Dir(d)) => d,
+            _ => panic!("should be directive"),
+        };
+        (elem, dir)
+    }
+
+    fn model_props(s: &str) -> (Vec<String>, bool) {
+        let (elem, mut dir) = parse_model(s);
+        let (value, runtime) = match convert_v_model(&mut dir, &elem, &NoopErrorHandler) {
+            DirectiveConvertResult::Converted { value, runtime } => (value, runtime),
+            _ => panic!("v-model should be converted"),
+        };
+        let keys = cast!(value, Js::Props)
+            .into_iter()
+            .map(|(k, _)| cast!(k, Js::StrLit).into_string())
+            .collect();
+        (keys, runtime.is_ok())
+    }
+
+    #[test]
+    fn test_custom_element_v_model() {
+        // custom elements use vModelText by default like native inputs
+        let (keys, has_runtime) = model_props("<my-input v-model='a'/>");
+        assert_eq!(keys, ["onUpdate:modelValue"]);
+        assert!(has_runtime);
+    }
+
+    #[test]
+    fn test_native_v_model_unchanged() {
+        let (keys, has_runtime) = model_props("<input v-model='a'/>");
+        assert_eq!(keys, ["onUpdate:modelValue"]);
+        assert!(has_runtime);
+    }
+
+    fn model_errors(s: &str) -> Vec<&'static str> {
+        let (elem, mut dir) = parse_model(s);
+        let eh = VecErrorHandler::new();
+        convert_v_model(&mut dir, &elem, &eh);
+        let errors = eh.errors();
+        errors.iter().map(|e| e.msg()).collect()
+    }
+
+    #[test]
+    fn test_v_model_on_invalid_element() {
+        let errors = model_errors("<div v-model='a'/>");
+        assert_eq!(
+            errors,
+            ["v-model can only be used on <input>, <textarea> and <select> elements."]
+        );
+        for case in [
+            "<input v-model='a'/>",
+            "<select v-model='a'/>",
+            "<textarea v-model='a'/>",
+            "<my-input v-model='a'/>",
+        ] {
+            assert!(model_errors(case).is_empty(), "{}", case);
+        }
+    }
+
+    fn model_runtime(s: &str) -> RuntimeHelper {
+        let (elem, mut dir) = parse_model(s);
+        match convert_v_model(&mut dir, &elem, &NoopErrorHandler) {
+            DirectiveConvertResult::Converted { runtime, .. } => {
+                runtime.expect("native v-model needs runtime directive")
+            }
+            _ => panic!("v-model should be converted"),
+        }
+    }
+
+    #[test]
+    fn test_v_model_runtime_by_type() {
+        let cases = [
+            ("<input v-model='a'/>", dh::V_MODEL_TEXT),
+            ("<input type='text' v-model='a'/>", dh::V_MODEL_TEXT),
+            ("<input type v-model='a'/>", dh::V_MODEL_TEXT),
+            ("<input type='checkbox' v-model='a'/>", dh::V_MODEL_CHECKBOX),
+            ("<input type='radio' v-model='a'/>", dh::V_MODEL_RADIO),
+            ("<select v-model='a'/>", dh::V_MODEL_SELECT),
+            ("<textarea v-model='a'/>", dh::V_MODEL_TEXT),
+            ("<input :type='t' v-model='a'/>", dh::V_MODEL_DYNAMIC),
+            ("<input v-bind='attrs' v-model='a'/>", dh::V_MODEL_DYNAMIC),
+        ];
+        for (case, expected) in cases {
+            assert_eq!(model_runtime(case), expected, "{}", case);
+        }
+    }
+}
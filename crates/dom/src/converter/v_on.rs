@@ -136,3 +136,39 @@ fn convert_click<'a>(key: Js<'a>, name: &'a str) -> Js<'a> {
 }
 
 pub const V_ON: DirectiveConverter = ("on", convert_v_on);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn modifier_names<'a>(value: &Js<'a>) -> Vec<&'a str> {
+        let args = match value {
+            Js::Call(dom_helper::V_ON_WITH_MODIFIERS, args) => args,
+            _ => panic!("handler should be wrapped by withModifiers"),
+        };
+        match &args[1] {
+            Js::Array(mods) => mods
+                .iter()
+                .map(|m| match m {
+                    Js::StrLit(s) => s.raw,
+                    _ => panic!("modifier should be string literal"),
+                })
+                .collect(),
+            _ => panic!("modifiers should be array"),
+        }
+    }
+
+    #[test]
+    fn test_guard_and_exact_mods() {
+        let key = Js::str_lit("onClick");
+        let resolved = resolve_modifiers(&["self", "exact"], &key);
+        let mut event = (key, Js::Src("h"));
+        apply_modifiers(&mut event, resolved);
+        assert_eq!(modifier_names(&event.1), ["self", "exact"]);
+        let key = Js::str_lit("onClick");
+        let resolved = resolve_modifiers(&["exact", "stop", "self"], &key);
+        let mut event = (key, Js::Src("h"));
+        apply_modifiers(&mut event, resolved);
+        assert_eq!(modifier_names(&event.1), ["exact", "stop", "self"]);
+    }
+}
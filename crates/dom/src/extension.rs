@@ -27,6 +27,7 @@ pub mod dom_helper {
     ];
 }
 
+#[derive(Debug)]
 pub enum DomError {
     VHtmlNoExpression,
     VHtmlWithChildren,
@@ -115,6 +115,7 @@ pub fn compile_option(error_handler: RcErrHandle) -> CompileOption {
         delimiters: ("{{".to_string(), "}}".to_string()),
         directive_converters: DOM_DIR_CONVERTERS.iter().copied().collect(),
         helper_strs: dom_helper::DOM_HELPER_MAP,
+        parse_static_style: true,
         error_handler,
         mode: ScriptMode::Function {
             prefix_identifier: true,
@@ -13,6 +13,10 @@ use compiler::transformer::{
     normalize_props::NormalizeProp,
     cache_handlers::CacheHandlers,
     hoist_static::HoistStatic,
+    lift_temps::TempLifter,
+    track_depth::DepthTracker,
+    warn_constant::ConstantWarner,
+    warn_dynamic_props::DynamicPropsWarner,
 };
 use compiler::converter::BaseConvertInfo;
 use compiler::{SFCInfo, chain};
@@ -23,7 +27,9 @@ pub fn get_dom_pass<'a>(
     sfc_info: &'a SFCInfo<'a>,
     opt: &CompileOption,
 ) -> impl CorePass<BaseConvertInfo<'a>> {
-    let prefix_identifier = opt.transforming().prefix_identifier;
+    let transform_option = opt.transforming();
+    // inline mode must prefix identifiers to resolve setup bindings
+    let prefix_identifier = transform_option.prefix_identifier || sfc_info.inline;
     let shared = chain![
         SlotFlagMarker,
         CacheHandlers::new(opt.cache_handlers),
@@ -31,19 +37,95 @@ pub fn get_dom_pass<'a>(
             prefix_identifier,
             sfc_info,
             err_handle: opt.error_handler.clone(),
+            global_allow_list: opt.global_allow_list.clone(),
         },
     ];
     chain![
-        PatchFlagMarker,
+        DepthTracker::new(transform_option.report_depth),
+        TempLifter,
+        ConstantWarner::new(opt.warn_constant, opt.error_handler.clone()),
+        DynamicPropsWarner::new(opt.max_dynamic_props, opt.error_handler.clone()),
+        PatchFlagMarker::new(transform_option.inject_key),
         UsageWarner(opt.error_handler.clone()),
         TextOptimizer,
-        EntityCollector::default(),
+        EntityCollector::new(opt.force_fragment_root).with_deny_list(
+            opt.deny_helpers.clone(),
+            opt.helper_strs,
+            opt.error_handler.clone(),
+        ),
         NormalizeProp,
         SharedInfoPasses {
             passes: shared,
             shared_info: Scope::default(),
             pd: PhantomData,
         },
-        HoistStatic::default(),
+        HoistStatic::new(opt.hoist_sink.clone()),
     ]
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use compiler::compiler::{BaseCompiler, TemplateCompiler};
+    use compiler::error::{CompilationErrorKind as ErrorKind, NoopErrorHandler, VecErrorHandler};
+    use std::rc::Rc;
+
+    fn dom_compile(s: &str) -> String {
+        let option = crate::compile_option(Rc::new(NoopErrorHandler));
+        let sfc_info = Default::default();
+        let compiler = BaseCompiler::new(Vec::new, get_dom_pass, option);
+        String::from_utf8(compiler.compile(s, &sfc_info).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_merge_class_style() {
+        let code = dom_compile("<p class='a' :class='b' style='color: red' :style='c'/>");
+        assert!(!code.contains("_directive_bind"), "{}", code);
+        assert!(
+            code.contains(r#"class: _normalizeClass(["a", _ctx.b])"#),
+            "{}",
+            code
+        );
+        assert!(code.contains("style: _normalizeStyle([{"), "{}", code);
+    }
+
+    #[test]
+    fn test_custom_element_v_model() {
+        let compile = |custom_element_v_model| {
+            let option = CompileOption {
+                is_custom_element: |tag| tag.contains('-'),
+                custom_element_v_model,
+                ..crate::compile_option(Rc::new(NoopErrorHandler))
+            };
+            let sfc_info = Default::default();
+            let compiler = BaseCompiler::new(Vec::new, get_dom_pass, option);
+            let case = "<my-input v-model='a'/>";
+            String::from_utf8(compiler.compile(case, &sfc_info).unwrap()).unwrap()
+        };
+        let code = compile(false);
+        assert!(code.contains("[_vModelText, _ctx.a]"), "{}", code);
+        assert!(!code.contains("modelValue:"), "{}", code);
+        let code = compile(true);
+        assert!(code.contains("modelValue: _ctx.a,"), "{}", code);
+        let handler = r#""onUpdate:modelValue": $event => ((_ctx.a) = $event)"#;
+        assert!(code.contains(handler), "{}", code);
+        assert!(!code.contains("vModelText"), "{}", code);
+    }
+
+    #[test]
+    fn test_warn_dynamic_props() {
+        let eh = Rc::new(VecErrorHandler::new());
+        let option = CompileOption {
+            max_dynamic_props: Some(1),
+            preserve_comments: Some(true),
+            ..crate::compile_option(eh.clone())
+        };
+        let sfc_info = Default::default();
+        let compiler = BaseCompiler::new(Vec::new, get_dom_pass, option);
+        let case = "<p :a='a' :b='b'/><!-- @vue-ignore --><p :a='a' :b='b'/>";
+        compiler.compile(case, &sfc_info).unwrap();
+        let errors = eh.errors();
+        let kinds: Vec<_> = errors.iter().map(|e| &e.kind).collect();
+        assert!(matches!(kinds[..], [ErrorKind::TooManyDynamicProps]));
+    }
+}
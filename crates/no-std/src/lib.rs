@@ -0,0 +1,42 @@
+//! Ensures the scanner and parser in vue-compiler-core build without std.
+//! Run `cargo build -p vue-compiler-no-std` to check.
+#![no_std]
+
+extern crate alloc;
+
+use alloc::rc::Rc;
+use compiler::{
+    error::NoopErrorHandler,
+    parser::{AstNode, AstRoot, ParseOption, Parser},
+    scanner::{ScanOption, Scanner},
+};
+
+pub fn parse(source: &str) -> AstRoot<'_> {
+    let scanner = Scanner::new(ScanOption::default());
+    let eh = Rc::new(NoopErrorHandler);
+    let tokens = scanner.scan(source, eh.clone());
+    Parser::new(ParseOption::default()).parse(tokens, eh)
+}
+
+/// Counts elements in the template by walking the AST.
+pub fn count_elements(source: &str) -> usize {
+    parse(source).children.iter().map(walk).sum()
+}
+
+fn walk(node: &AstNode) -> usize {
+    match node {
+        AstNode::Element(e) => 1 + e.children.iter().map(walk).sum::<usize>(),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_without_std() {
+        assert_eq!(count_elements("<div><p>{{a}}</p><span/></div>"), 3);
+        assert_eq!(count_elements("text {{ only }}"), 0);
+    }
+}
@@ -87,6 +87,7 @@ impl<'a> SfcBlock<'a> {
     }
 }
 
+#[derive(Debug)]
 pub enum SfcError {
     DeprecatedFunctionalTemplate,
     DeprecatedStyleVars,
@@ -0,0 +1,18 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use std::rc::Rc;
+use vue_compiler_core::compiler::CompileOption;
+use vue_compiler_core::error::NoopErrorHandler;
+use vue_compiler_core::parser::Parser;
+use vue_compiler_core::scanner::Scanner;
+
+fuzz_target!(|data: &[u8]| {
+    let source = match std::str::from_utf8(data) {
+        Ok(s) => s,
+        Err(_) => return,
+    };
+    let option = CompileOption::default();
+    let eh = Rc::new(NoopErrorHandler);
+    let tokens = Scanner::new(option.scanning()).scan(source, eh.clone());
+    Parser::new(option.parsing()).parse(tokens, eh);
+});